@@ -1,10 +1,24 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::cookie::{time, Cookie, SameSite};
+use actix_web::{http::header, web, web::Bytes, HttpRequest, HttpResponse, Result};
 use chrono::Utc;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tracing::{error, info};
 
-use crate::api::types::{ApiError, VerificationRequest, VerificationResult};
+use crate::api::types::{
+    ApiError, DeletionReceipt, DisclosureMode, RevocationRequest, Revoker, TimeWindow,
+    VerificationRequest, VerificationResult, VerificationType,
+};
+use crate::auth::{Principal, LOGIN_STATE_COOKIE, SESSION_COOKIE};
+use crate::utils::audit::AuditEvent;
+use crate::utils::progress::{ProgressReporter, VerificationPhase};
+use crate::utils::status_list;
+use crate::utils::transparency::LogError;
+use crate::utils::validation;
 use crate::verification::engine::VerificationEngine;
+use crate::verification::proof::ProofData;
+use crate::webhook::WebhookPayload;
 use crate::{utils::errors::AppError, AppState};
 
 pub async fn verify(
@@ -13,7 +27,7 @@ pub async fn verify(
 ) -> Result<HttpResponse> {
     info!("Verification request for user: {}", req.github_username);
 
-    match verify_internal(app_state, req.into_inner()).await {
+    match verify_internal(app_state, req.into_inner(), &ProgressReporter::disabled()).await {
         Ok(result) => {
             info!(
                 "Verification completed successfully for user: {}",
@@ -28,50 +42,226 @@ pub async fn verify(
     }
 }
 
+/// Same as `verify`, but responds with a `text/event-stream` of `progress`
+/// events followed by a single `result` or `error` event.
+pub async fn verify_stream(
+    app_state: AppState,
+    req: web::Json<VerificationRequest>,
+) -> Result<HttpResponse> {
+    info!(
+        "Streaming verification request for user: {}",
+        req.github_username
+    );
+
+    let (progress, receiver) = ProgressReporter::channel();
+    let task = actix_web::rt::spawn(async move {
+        // `progress` is dropped when verification finishes, which closes the channel
+        verify_internal(app_state, req.into_inner(), &progress).await
+    });
+
+    let events = stream::unfold(Some((receiver, task)), |state| async move {
+        let (mut receiver, task) = state?;
+        match receiver.recv().await {
+            Some(event) => Some((sse_frame("progress", &event), Some((receiver, task)))),
+            None => {
+                let frame = match task.await {
+                    Ok(Ok(result)) => {
+                        info!(
+                            "Streaming verification completed for user: {}",
+                            result.username
+                        );
+                        sse_frame("result", &result)
+                    }
+                    Ok(Err(err)) => {
+                        error!("Streaming verification failed: {}", err);
+                        sse_frame("error", &err.to_api_error().1)
+                    }
+                    Err(err) => {
+                        error!("Streaming verification task failed: {}", err);
+                        sse_frame(
+                            "error",
+                            &AppError::Internal(err.to_string()).to_api_error().1,
+                        )
+                    }
+                };
+                Some((frame, None))
+            }
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events.map(Ok::<_, actix_web::Error>)))
+}
+
+fn sse_frame<T: Serialize>(event: &str, data: &T) -> Bytes {
+    let data = serde_json::to_string(data).unwrap_or_else(|_| "null".to_string());
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
 async fn verify_internal(
     app_state: AppState,
-    req: VerificationRequest,
+    mut req: VerificationRequest,
+    progress: &ProgressReporter,
 ) -> Result<VerificationResult, AppError> {
     // 1. Input validation
-    crate::utils::validation::validate_github_username(&req.github_username)?;
+    validation::validate_github_username(&req.github_username)?;
 
-    let threshold = req
-        .threshold
-        .unwrap_or_else(|| req.verification_type.default_threshold());
-    if threshold == 0 || threshold > 10000 {
-        return Err(AppError::Validation(
-            "Threshold must be between 1 and 10000".to_string(),
-        ));
+    if let Some(callback_url) = &req.callback_url {
+        if !app_state.webhook_client.is_enabled() {
+            return Err(AppError::Validation(
+                "Webhook callbacks are not configured on this server".to_string(),
+            ));
+        }
+        validation::validate_callback_url(callback_url, app_state.webhook_client.allowed_hosts())?;
     }
 
-    // 2. GitHub API calls
-    let github_client = &app_state.github_client;
-    let events = github_client
-        .fetch_user_events(&req.github_username)
-        .await?;
+    validation::validate_criteria_options(req.verification_type, &req.options)?;
 
-    // 3. Verification logic
-    let engine = VerificationEngine::new();
-    let meets_criteria = engine
-        .verify_criteria(&events, req.verification_type, threshold)
-        .await?;
+    if let Some(nonce) = &req.nonce {
+        validation::validate_nonce(nonce)?;
+    }
+
+    // Time-based proofs always carry their window, so that a proof for the
+    // default "last 365 days" can't be mistaken for a calendar year
+    if req.verification_type.is_time_based() {
+        req.options.window.get_or_insert_with(TimeWindow::default);
+    }
+
+    // Bucketed proofs are evaluated against the lowest bucket and then
+    // report the highest one reached
+    let bucketed = req.disclosure == DisclosureMode::Bucketed;
+    let threshold = if bucketed {
+        if req.threshold.is_some() {
+            return Err(AppError::Validation(
+                "Bucketed disclosure doesn't accept a threshold".to_string(),
+            ));
+        }
+        if req.options.histogram.is_some() {
+            return Err(AppError::Validation(
+                "Bucketed disclosure can't include a histogram".to_string(),
+            ));
+        }
+        *req.verification_type.buckets().first().ok_or_else(|| {
+            AppError::Validation(format!(
+                "{:?} doesn't support bucketed disclosure",
+                req.verification_type
+            ))
+        })?
+    } else {
+        req.threshold
+            .unwrap_or_else(|| req.verification_type.default_threshold())
+    };
+    let max_threshold = req.verification_type.max_threshold();
+    if threshold == 0 || threshold > max_threshold {
+        return Err(AppError::Validation(format!(
+            "Threshold must be between 1 and {}",
+            max_threshold
+        )));
+    }
+
+    app_state.audit.record(AuditEvent::VerificationRequested {
+        subject: app_state.audit.pseudonym(&req.github_username),
+        verification_type: req.verification_type,
+    });
+
+    // Repeated requests for the same user and criterion could probe the
+    // exact value one threshold at a time
+    let throttle_key = format!(
+        "{}:{}",
+        req.github_username.to_ascii_lowercase(),
+        serde_json::to_string(&req.verification_type).unwrap()
+    );
+    if !app_state.verification_throttle.try_acquire(&throttle_key) {
+        let throttle = &app_state.verification_throttle;
+        return Err(AppError::Throttled(format!(
+            "At most {} verifications of this criterion per user every {} minutes",
+            throttle.limit(),
+            throttle.window().num_minutes()
+        )));
+    }
+
+    let redacted_progress;
+    let progress = if bucketed {
+        redacted_progress = progress.without_counts();
+        &redacted_progress
+    } else {
+        progress
+    };
+
+    // 2. Verification logic (fetches whatever GitHub data the criterion needs)
+    app_state.audit.record(AuditEvent::DataSourceUsed {
+        subject: app_state.audit.pseudonym(&req.github_username),
+        data_source: app_state.github.name(),
+    });
+    let engine = VerificationEngine::new(app_state.github.clone())
+        .with_anomaly_policy(app_state.anomaly_policy);
+    let outcome = engine
+        .verify_criteria(
+            &req.github_username,
+            req.verification_type,
+            threshold,
+            &req.options,
+            progress,
+        )
+        .await
+        .inspect_err(|err| {
+            // An outage or rate limit reveals nothing about the user, so it
+            // doesn't count against their allowance
+            if matches!(err, AppError::GitHub(github_err) if github_err.is_upstream()) {
+                app_state.verification_throttle.release(&throttle_key);
+            }
+        })?;
+
+    // Evidence carries exact values, so bucketed proofs go without it
+    let (threshold, meets_criteria, evidence) = if bucketed {
+        let bucket = outcome
+            .meets_criteria
+            .then(|| req.verification_type.bucket_for(outcome.value))
+            .flatten();
+        (bucket.unwrap_or(threshold), bucket.is_some(), None)
+    } else {
+        (threshold, outcome.meets_criteria, outcome.evidence)
+    };
 
     let verified_at = Utc::now();
+    let anomaly_policy = req
+        .verification_type
+        .is_commit_based()
+        .then_some(app_state.anomaly_policy);
 
-    // 4. Generate proof only if verification succeeds
-    let (attestation_token, attestation_claims, proof_hash) = if meets_criteria {
-        let proof_data = format!(
-            "{}:{}:{}:{}",
-            req.github_username,
-            serde_json::to_string(&req.verification_type).unwrap(),
+    // 3. Generate proof only if verification succeeds
+    let (attestation_token, attestation_claims, proof_hash, status_entry) = if meets_criteria {
+        // The revocation bit is fixed before hashing so that it is attested
+        let status_entry = app_state.proof_storage.allocate_status_entry();
+        let hash = ProofData {
+            username: &req.github_username,
+            verification_type: req.verification_type,
+            options: &req.options,
+            disclosure: req.disclosure,
+            threshold,
             meets_criteria,
-            verified_at.timestamp()
-        );
-        let hash = format!("{:x}", Sha256::digest(proof_data.as_bytes()));
+            verified_at,
+            histogram: outcome.histogram.as_ref(),
+            anomaly_policy,
+            status_list_index: status_entry.status_list_index,
+            status_list_credential: status_entry.status_list_credential,
+        }
+        .hash();
 
         // MAA attestation for successful verification
+        progress.report(
+            VerificationPhase::Attesting,
+            "Requesting MAA attestation token",
+            None,
+        );
         let (token, claims) = if !app_state.maa_client.endpoint.is_empty() {
-            match app_state.maa_client.get_attestation_token(&hash).await {
+            match app_state
+                .maa_client
+                .get_attestation_token(&hash, req.nonce.as_deref(), Some(status_entry))
+                .await
+            {
                 Ok(jwt_token) => {
                     // JWT claimsも解析
                     let parsed_claims = app_state
@@ -86,6 +276,10 @@ async fn verify_internal(
                 }
                 Err(err) => {
                     error!("MAA attestation failed: {}", err);
+                    app_state.audit.record(AuditEvent::MaaFailure {
+                        purpose: "proof",
+                        error: err.to_string(),
+                    });
                     (Some("MAA_UNAVAILABLE".to_string()), None)
                 }
             }
@@ -93,33 +287,56 @@ async fn verify_internal(
             (Some("MAA_NOT_CONFIGURED".to_string()), None)
         };
 
-        (token, claims, Some(hash))
+        (token, claims, Some(hash), Some(status_entry))
     } else {
         info!(
             "Verification failed - no proof generated for user: {}",
             req.github_username
         );
-        (None, None, None)
+        (None, None, None, None)
     };
 
-    // 5. Create result
-    let result = VerificationResult {
+    // 4. Create result
+    let mut result = VerificationResult {
         username: req.github_username,
         verification_type: req.verification_type,
         threshold,
+        options: req.options,
+        disclosure: req.disclosure,
         meets_criteria,
+        evidence,
+        histogram: outcome.histogram,
+        anomaly_policy,
         attestation_token,
         attestation_claims,
+        nonce: req.nonce,
         verified_at,
         proof_hash: proof_hash.clone(),
+        log_index: None,
+        status: None,
     };
 
-    // 6. Store proof only if verification succeeded
-    if let Some(hash) = proof_hash {
-        app_state
+    // 5. Store proof only if verification succeeded
+    if let (Some(hash), Some(status_entry)) = (proof_hash, status_entry) {
+        progress.report(VerificationPhase::Storing, "Storing proof", None);
+        result = app_state
             .proof_storage
-            .store_proof(hash, result.clone())
+            .store_proof(hash.clone(), status_entry, result)
             .await;
+        app_state.audit.record(AuditEvent::ProofIssued {
+            subject: app_state.audit.pseudonym(&result.username),
+            verification_type: result.verification_type,
+            proof_hash: hash.clone(),
+            log_index: result.log_index,
+        });
+
+        // 6. Notify the integrator once the proof is retrievable
+        if let Some(callback_url) = req.callback_url {
+            app_state.webhook_client.dispatch(
+                callback_url,
+                WebhookPayload::verification_completed(&hash, &result),
+            );
+        }
     }
 
     Ok(result)
@@ -129,7 +346,7 @@ pub async fn get_proof(app_state: AppState, path: web::Path<String>) -> Result<H
     let proof_hash = path.into_inner();
 
     // Validate proof hash format
-    if !proof_hash.chars().all(|c| c.is_ascii_hexdigit()) || proof_hash.len() != 64 {
+    if !validation::is_valid_proof_hash(&proof_hash) {
         return Ok(HttpResponse::BadRequest().json(ApiError {
             error: "Invalid proof hash format".to_string(),
             error_code: "INVALID_PROOF_HASH".to_string(),
@@ -152,3 +369,476 @@ pub async fn get_proof(app_state: AppState, path: web::Path<String>) -> Result<H
         }
     }
 }
+
+/// Renders a proof's attested histogram as an SVG heatmap for embedding.
+pub async fn get_proof_histogram_svg(
+    app_state: AppState,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let proof_hash = path.into_inner();
+
+    if !validation::is_valid_proof_hash(&proof_hash) {
+        return Ok(HttpResponse::BadRequest().json(ApiError {
+            error: "Invalid proof hash format".to_string(),
+            error_code: "INVALID_PROOF_HASH".to_string(),
+            details: None,
+        }));
+    }
+
+    let histogram = app_state
+        .proof_storage
+        .get_proof(&proof_hash)
+        .await
+        .and_then(|result| Some((result.histogram?, result.username, result.verification_type)));
+
+    match histogram {
+        Some((histogram, username, verification_type)) => {
+            let caption = format!(
+                "{} · {} · proof {}",
+                username,
+                serde_json::to_string(&verification_type)
+                    .unwrap()
+                    .trim_matches('"'),
+                &proof_hash[..12]
+            );
+            Ok(HttpResponse::Ok()
+                .content_type("image/svg+xml")
+                .insert_header(("Cache-Control", "public, max-age=3600"))
+                .body(histogram.to_svg(&caption)))
+        }
+        None => Ok(HttpResponse::NotFound().json(ApiError {
+            error: "Histogram not found".to_string(),
+            error_code: "HISTOGRAM_NOT_FOUND".to_string(),
+            details: Some(
+                "The proof may have expired, never existed or was issued without a histogram"
+                    .to_string(),
+            ),
+        })),
+    }
+}
+
+/// Delivery log of a proof's webhook callbacks. Allowed for the proof's
+/// subject, signed in with GitHub, and for the operator; once the proof has
+/// expired or was erased, only for the operator.
+pub async fn get_webhook_deliveries(
+    app_state: AppState,
+    http_req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let proof_hash = path.into_inner();
+
+    if !validation::is_valid_proof_hash(&proof_hash) {
+        return Ok(HttpResponse::BadRequest().json(ApiError {
+            error: "Invalid proof hash format".to_string(),
+            error_code: "INVALID_PROOF_HASH".to_string(),
+            details: None,
+        }));
+    }
+
+    let authorized = match app_state.proof_storage.get_proof(&proof_hash).await {
+        Some(proof) => app_state
+            .auth
+            .require(&http_req, &proof.username)
+            .map(|_| ()),
+        None => match app_state.auth.authenticate(&http_req) {
+            Some(Principal::Operator) => Ok(()),
+            Some(Principal::Subject(_)) => Err(AppError::Forbidden(
+                "Only the operator may read deliveries of an expired proof".to_string(),
+            )),
+            None => Err(AppError::Unauthorized(
+                "Sign in with GitHub or provide the operator key".to_string(),
+            )),
+        },
+    };
+    if let Err(err) = authorized {
+        return Ok(err.into());
+    }
+
+    let deliveries = app_state.webhook_client.deliveries_for(&proof_hash);
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+const MAX_REASON_LENGTH: usize = 500;
+
+/// Revokes a proof. Allowed for the proof's subject, signed in with GitHub,
+/// and for the operator.
+pub async fn revoke_proof(
+    app_state: AppState,
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    body: Option<web::Json<RevocationRequest>>,
+) -> Result<HttpResponse> {
+    let proof_hash = path.into_inner();
+    let reason = body.and_then(|body| body.into_inner().reason);
+
+    match revoke_proof_internal(&app_state, &http_req, &proof_hash, reason).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(result)),
+        Err(err) => {
+            error!("Revocation of {} failed: {}", proof_hash, err);
+            Ok(err.into())
+        }
+    }
+}
+
+async fn revoke_proof_internal(
+    app_state: &AppState,
+    http_req: &HttpRequest,
+    proof_hash: &str,
+    reason: Option<String>,
+) -> Result<VerificationResult, AppError> {
+    if !validation::is_valid_proof_hash(proof_hash) {
+        return Err(AppError::Validation(
+            "Invalid proof hash format".to_string(),
+        ));
+    }
+    if reason
+        .as_ref()
+        .is_some_and(|reason| reason.chars().count() > MAX_REASON_LENGTH)
+    {
+        return Err(AppError::Validation(format!(
+            "reason must be at most {} characters",
+            MAX_REASON_LENGTH
+        )));
+    }
+
+    let proof = app_state
+        .proof_storage
+        .get_proof(proof_hash)
+        .await
+        .ok_or_else(|| AppError::NotFound("Proof not found".to_string()))?;
+    let revoked_by = match app_state.auth.require(http_req, &proof.username)? {
+        Principal::Subject(_) => Revoker::Subject,
+        Principal::Operator => Revoker::Operator,
+    };
+
+    let revoked = app_state
+        .proof_storage
+        .revoke_proof(proof_hash, revoked_by, reason)
+        .await
+        .ok_or_else(|| AppError::NotFound("Proof not found".to_string()))?;
+    app_state.audit.record(AuditEvent::ProofRevoked {
+        proof_hash: proof_hash.to_string(),
+        revoked_by,
+    });
+    Ok(revoked)
+}
+
+/// Bitstring Status List document covering every proof issued, for offline
+/// revocation checks.
+pub async fn get_status_list(app_state: AppState, http_req: HttpRequest) -> Result<HttpResponse> {
+    let base_url = {
+        let connection = http_req.connection_info();
+        format!("{}://{}", connection.scheme(), connection.host())
+    };
+    let encoded_list = app_state.proof_storage.encoded_status_list().await;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-cache"))
+        .json(status_list::status_list_document(
+            &base_url,
+            encoded_list,
+            Utc::now(),
+        )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginQuery {
+    return_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    code: String,
+    state: String,
+}
+
+/// Redirects to GitHub to sign in. `return_to` must be a path on this site.
+pub async fn github_login(
+    app_state: AppState,
+    query: web::Query<LoginQuery>,
+) -> Result<HttpResponse> {
+    let return_to = query
+        .into_inner()
+        .return_to
+        .filter(|path| path.starts_with('/') && !path.starts_with("//") && !path.contains('\\'))
+        .unwrap_or_else(|| "/".to_string());
+
+    match app_state.auth.oauth.authorize_url(return_to) {
+        Ok((url, state)) => {
+            let timeout = app_state.auth.oauth.login_timeout().num_seconds();
+            let cookie = Cookie::build(LOGIN_STATE_COOKIE, state)
+                .path("/auth/github")
+                .http_only(true)
+                .secure(true)
+                .same_site(SameSite::Lax)
+                .max_age(time::Duration::seconds(timeout))
+                .finish();
+            Ok(HttpResponse::Found()
+                .insert_header((header::LOCATION, url))
+                .cookie(cookie)
+                .finish())
+        }
+        Err(err) => Ok(AppError::from(err).into()),
+    }
+}
+
+pub async fn github_callback(
+    app_state: AppState,
+    http_req: HttpRequest,
+    query: web::Query<CallbackQuery>,
+) -> Result<HttpResponse> {
+    let browser_state = http_req.cookie(LOGIN_STATE_COOKIE);
+    match app_state
+        .auth
+        .oauth
+        .complete(
+            &query.code,
+            &query.state,
+            browser_state.as_ref().map(|cookie| cookie.value()),
+        )
+        .await
+    {
+        Ok((login, return_to)) => {
+            let session_id = app_state.auth.sessions.create(&login);
+            let max_age = app_state.auth.sessions.max_age().num_seconds();
+            let cookie = Cookie::build(SESSION_COOKIE, session_id)
+                .path("/")
+                .http_only(true)
+                .secure(true)
+                .same_site(SameSite::Lax)
+                .max_age(time::Duration::seconds(max_age))
+                .finish();
+            let mut state_removal = Cookie::build(LOGIN_STATE_COOKIE, "")
+                .path("/auth/github")
+                .finish();
+            state_removal.make_removal();
+            Ok(HttpResponse::Found()
+                .insert_header((header::LOCATION, return_to))
+                .cookie(cookie)
+                .cookie(state_removal)
+                .finish())
+        }
+        Err(err) => {
+            error!("GitHub sign-in failed: {}", err);
+            Ok(AppError::from(err).into())
+        }
+    }
+}
+
+/// The signed-in GitHub login, if any.
+pub async fn get_session(app_state: AppState, http_req: HttpRequest) -> Result<HttpResponse> {
+    match app_state.auth.authenticate(&http_req) {
+        Some(Principal::Subject(login)) => {
+            Ok(HttpResponse::Ok().json(serde_json::json!({ "login": login })))
+        }
+        Some(Principal::Operator) => {
+            Ok(HttpResponse::Ok().json(serde_json::json!({ "operator": true })))
+        }
+        None => Ok(AppError::Unauthorized("Not signed in".to_string()).into()),
+    }
+}
+
+pub async fn logout(app_state: AppState, http_req: HttpRequest) -> Result<HttpResponse> {
+    if let Some(cookie) = http_req.cookie(SESSION_COOKIE) {
+        app_state.auth.sessions.remove(cookie.value());
+    }
+    let mut removal = Cookie::build(SESSION_COOKIE, "").path("/").finish();
+    removal.make_removal();
+    Ok(HttpResponse::NoContent().cookie(removal).finish())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InclusionQuery {
+    tree_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyQuery {
+    first: u64,
+    second: Option<u64>,
+}
+
+/// Current transparency log head and the latest attested one.
+pub async fn get_log_root(app_state: AppState) -> Result<HttpResponse> {
+    let log = app_state.proof_storage.log();
+    let head = log.head();
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "tree_size": head.tree_size,
+        "root_hash": head.root_hash,
+        "signed_tree_head": log.signed_head(),
+    })))
+}
+
+pub async fn get_log_inclusion(
+    app_state: AppState,
+    path: web::Path<String>,
+    query: web::Query<InclusionQuery>,
+) -> Result<HttpResponse> {
+    let proof_hash = path.into_inner();
+    if !validation::is_valid_proof_hash(&proof_hash) {
+        return Ok(AppError::Validation("Invalid proof hash format".to_string()).into());
+    }
+
+    match app_state
+        .proof_storage
+        .log()
+        .inclusion_proof(&proof_hash, query.tree_size)
+    {
+        Ok(proof) => Ok(HttpResponse::Ok().json(proof)),
+        Err(err @ LogError::UnknownLeaf(_)) => Ok(AppError::NotFound(err.to_string()).into()),
+        Err(err) => Ok(AppError::Validation(err.to_string()).into()),
+    }
+}
+
+pub async fn get_log_consistency(
+    app_state: AppState,
+    query: web::Query<ConsistencyQuery>,
+) -> Result<HttpResponse> {
+    let log = app_state.proof_storage.log();
+    let second = query.second.unwrap_or_else(|| log.head().tree_size);
+
+    match log.consistency_proof(query.first, second) {
+        Ok(proof) => Ok(HttpResponse::Ok().json(proof)),
+        Err(err) => Ok(AppError::Validation(err.to_string()).into()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserProofsQuery {
+    verification_type: Option<VerificationType>,
+}
+
+/// Lists a user's live proofs. Only the user, signed in with GitHub, and
+/// the operator may list them.
+pub async fn get_user_proofs(
+    app_state: AppState,
+    http_req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<UserProofsQuery>,
+) -> Result<HttpResponse> {
+    let username = path.into_inner();
+    if let Err(err) = validation::validate_github_username(&username)
+        .and_then(|_| app_state.auth.require(&http_req, &username))
+    {
+        return Ok(err.into());
+    }
+
+    let proofs = app_state
+        .proof_storage
+        .get_user_proofs(&username, query.verification_type)
+        .await;
+    info!("Listed {} proofs for user: {}", proofs.len(), username);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "username": username,
+        "proofs": proofs,
+    })))
+}
+
+/// What an erasure leaves behind, as listed in every deletion receipt.
+/// Transparency log leaves are hashes of the proof hashes, which are
+/// guessable from a username and criterion, and can't be removed without
+/// invalidating every signed tree head.
+const NOT_PURGED: [&str; 3] = [
+    "transparency_log_leaves",
+    "audit_log_tombstones",
+    "proof_copies_held_by_relying_parties",
+];
+
+/// Right to erasure: deletes the user's proofs, their transparency log and
+/// webhook delivery entries, and every cached GitHub response, revokes the
+/// erased proofs, then returns an attested receipt of what was deleted.
+pub async fn erase_user(
+    app_state: AppState,
+    http_req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let username = path.into_inner();
+
+    match erase_user_internal(&app_state, &http_req, &username).await {
+        Ok(receipt) => Ok(HttpResponse::Ok().json(receipt)),
+        Err(err) => {
+            error!("Erasure for {} failed: {}", username, err);
+            Ok(err.into())
+        }
+    }
+}
+
+async fn erase_user_internal(
+    app_state: &AppState,
+    http_req: &HttpRequest,
+    username: &str,
+) -> Result<DeletionReceipt, AppError> {
+    validation::validate_github_username(username)?;
+    let principal = app_state.auth.require(http_req, username)?;
+
+    let cached_responses_deleted = app_state.github.forget_user(username).map_err(|err| {
+        AppError::Internal(format!("Failed to delete cached GitHub responses: {}", err))
+    })?;
+    let erased = app_state.proof_storage.erase_user(username).await;
+    let webhook_deliveries_deleted = app_state
+        .webhook_client
+        .forget_deliveries(&erased.proof_hashes);
+
+    let deleted_at = Utc::now();
+    let revoked_status_indexes: Vec<String> = erased
+        .revoked_status_indexes
+        .iter()
+        .map(|index| index.to_string())
+        .collect();
+    let receipt_data = format!(
+        "erasure:{}:{}:{}:{}:{}:{}:{}",
+        username.to_ascii_lowercase(),
+        deleted_at.timestamp(),
+        erased.proof_hashes.join(","),
+        revoked_status_indexes.join(","),
+        NOT_PURGED.join(","),
+        cached_responses_deleted,
+        webhook_deliveries_deleted
+    );
+    let receipt_hash = format!("{:x}", Sha256::digest(receipt_data.as_bytes()));
+    let attestation_token = if app_state.maa_client.endpoint.is_empty() {
+        "MAA_NOT_CONFIGURED".to_string()
+    } else {
+        match app_state
+            .maa_client
+            .get_attestation_token(&receipt_hash, None, None)
+            .await
+        {
+            Ok(token) => token,
+            Err(err) => {
+                error!("MAA attestation of deletion receipt failed: {}", err);
+                app_state.audit.record(AuditEvent::MaaFailure {
+                    purpose: "deletion_receipt",
+                    error: err.to_string(),
+                });
+                "MAA_UNAVAILABLE".to_string()
+            }
+        }
+    };
+
+    info!(
+        "Erased data of user {} at the request of {:?}: {} proofs, receipt {}",
+        username,
+        principal,
+        erased.proof_hashes.len(),
+        receipt_hash
+    );
+    app_state.audit.record(AuditEvent::UserErased {
+        subject: app_state.audit.pseudonym(username),
+        proofs_deleted: erased.proof_hashes.len(),
+        receipt_hash: receipt_hash.clone(),
+    });
+
+    Ok(DeletionReceipt {
+        username: username.to_string(),
+        proof_hashes: erased.proof_hashes,
+        revoked_status_indexes: erased.revoked_status_indexes,
+        not_purged: NOT_PURGED.to_vec(),
+        cached_responses_deleted,
+        webhook_deliveries_deleted,
+        deleted_at,
+        receipt_hash,
+        attestation_token,
+    })
+}
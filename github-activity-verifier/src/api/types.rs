@@ -1,11 +1,206 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::verification::anomaly::AnomalyPolicy;
+use crate::verification::histogram::{ActivityHistogram, HistogramGranularity};
+
 #[derive(Debug, Deserialize)]
 pub struct VerificationRequest {
     pub github_username: String,
     pub verification_type: VerificationType,
     pub threshold: Option<u32>,
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    #[serde(default)]
+    pub options: CriteriaOptions,
+    #[serde(default)]
+    pub disclosure: DisclosureMode,
+    /// Verifier-chosen challenge, bound into the attestation token's runtime
+    /// data so the verifier can tell the token was made for its request
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+/// How much a proof reveals about the measured value.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisclosureMode {
+    /// Whether the value reaches the requested threshold, with evidence
+    #[default]
+    Threshold,
+    /// Only the highest fixed bucket the value reaches (e.g. 500+), without
+    /// evidence, so repeated requests can't narrow the value down
+    Bucketed,
+}
+
+impl DisclosureMode {
+    pub fn is_default(&self) -> bool {
+        *self == DisclosureMode::Threshold
+    }
+}
+
+/// Per-request tuning of a criterion.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct CriteriaOptions {
+    /// Time window for time-based criteria (default: the last 365 days)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<TimeWindow>,
+    /// Leave out activity in repositories owned by the user
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exclude_own_repos: bool,
+    /// Only count reviews that approved or requested changes
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub substantive_reviews_only: bool,
+    /// Organization for `org_membership`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    /// Repository (`owner/name`) for `repo_permission`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Repositories (`owner/name`) that commit, pull request, issue and
+    /// review criteria are scoped to; required for `repo_commits`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub repos: Vec<String>,
+    /// Language for `language_repos` and `language_bytes`, as named by GitHub
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Leave out forked repositories; the default depends on the criterion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_forks: Option<bool>,
+    /// Leave out archived repositories from star and repo counts
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exclude_archived: bool,
+    /// Leave out template repositories from star and repo counts
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exclude_templates: bool,
+    /// Leave out repositories without any commit authored by the user from
+    /// star and repo counts (one extra request per repository)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exclude_repos_without_commits: bool,
+    /// Leave out repositories created in the last N days from star and repo counts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_repo_age_days: Option<u32>,
+    /// IANA time zone that activity days are counted in (default UTC)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    /// Which streak `consecutive_days` attests (default: longest)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streak: Option<StreakMode>,
+    /// Only count push, pull request, review and issue events as activity
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub contribution_events_only: bool,
+    /// Number of recent commits sampled by `signed_commits` (default 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_size: Option<u32>,
+    /// Minimum role for `repo_permission` (default: push)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<RepoPermission>,
+    /// Attest a per-day or per-week activity histogram alongside the proof
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<HistogramGranularity>,
+}
+
+impl CriteriaOptions {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn window(&self) -> TimeWindow {
+        self.window.unwrap_or_default()
+    }
+
+    /// Whether star and repo counts need the repository list to be filtered.
+    pub fn has_repo_filters(&self) -> bool {
+        self.exclude_forks == Some(true)
+            || self.exclude_archived
+            || self.exclude_templates
+            || self.exclude_repos_without_commits
+            || self.min_repo_age_days.is_some()
+    }
+}
+
+/// The period a time-based criterion looks at. Calendar years and explicit
+/// ranges are in UTC; `to` is inclusive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimeWindow {
+    Rolling { days: u32 },
+    CalendarYear { year: i32 },
+    Range { from: NaiveDate, to: NaiveDate },
+}
+
+impl Default for TimeWindow {
+    fn default() -> Self {
+        TimeWindow::Rolling { days: 365 }
+    }
+}
+
+impl TimeWindow {
+    /// The window as a half-open `[from, to)` interval of instants.
+    pub fn resolve(&self, now: DateTime<Utc>) -> ResolvedWindow {
+        let start_of = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+        let (from, to) = match *self {
+            TimeWindow::Rolling { days } => (now - Duration::days(days as i64), now),
+            TimeWindow::CalendarYear { year } => (
+                start_of(NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default()),
+                start_of(NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap_or_default()),
+            ),
+            TimeWindow::Range { from, to } => (start_of(from), start_of(to + Duration::days(1))),
+        };
+
+        ResolvedWindow {
+            window: *self,
+            from,
+            to: to.min(now),
+        }
+    }
+}
+
+/// A `TimeWindow` pinned to concrete instants at verification time.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ResolvedWindow {
+    #[serde(flatten)]
+    pub window: TimeWindow,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl ResolvedWindow {
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        instant >= self.from && instant < self.to
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreakMode {
+    /// The longest streak anywhere in the available activity
+    #[default]
+    Longest,
+    /// The streak that is still running, i.e. ends today or yesterday
+    Current,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoPermission {
+    #[default]
+    Push,
+    Maintain,
+    Admin,
+}
+
+impl RepoPermission {
+    /// Rank comparable with `GitHubCollaboratorPermission::rank`.
+    pub fn rank(&self) -> u8 {
+        match self {
+            RepoPermission::Push => 2,
+            RepoPermission::Maintain => 3,
+            RepoPermission::Admin => 4,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -15,6 +210,17 @@ pub enum VerificationType {
     ConsecutiveDays,
     TotalStars,
     PublicRepos,
+    MergedPullRequests,
+    OpenedIssues,
+    CodeReviews,
+    AccountAgeDays,
+    Followers,
+    OrgMembership,
+    RepoPermission,
+    RepoCommits,
+    LanguageRepos,
+    LanguageBytes,
+    SignedCommits,
 }
 
 impl VerificationType {
@@ -24,23 +230,171 @@ impl VerificationType {
             VerificationType::ConsecutiveDays => 100,
             VerificationType::TotalStars => 1000,
             VerificationType::PublicRepos => 10,
+            VerificationType::MergedPullRequests => 10,
+            VerificationType::OpenedIssues => 10,
+            VerificationType::CodeReviews => 50,
+            VerificationType::AccountAgeDays => 365,
+            VerificationType::Followers => 100,
+            VerificationType::OrgMembership => 1,
+            VerificationType::RepoPermission => 1,
+            VerificationType::RepoCommits => 50,
+            VerificationType::LanguageRepos => 10,
+            VerificationType::LanguageBytes => 512_000,
+            VerificationType::SignedCommits => 80,
         }
     }
+
+    /// Criteria that look at activity within a `TimeWindow`.
+    pub fn is_time_based(&self) -> bool {
+        matches!(
+            self,
+            VerificationType::YearlyCommits
+                | VerificationType::ConsecutiveDays
+                | VerificationType::MergedPullRequests
+                | VerificationType::OpenedIssues
+                | VerificationType::CodeReviews
+                | VerificationType::RepoCommits
+                | VerificationType::SignedCommits
+        )
+    }
+
+    /// Criteria counting pushed commits, which the anomaly analysis checks.
+    pub fn is_commit_based(&self) -> bool {
+        matches!(
+            self,
+            VerificationType::YearlyCommits | VerificationType::ConsecutiveDays
+        )
+    }
+
+    /// Membership and permission checks are yes/no facts, attested with a
+    /// threshold of exactly 1. Signed commits are a percentage. Language bytes
+    /// are measured in bytes, so they allow much larger thresholds than the
+    /// count-based criteria.
+    pub fn max_threshold(&self) -> u32 {
+        match self {
+            VerificationType::OrgMembership | VerificationType::RepoPermission => 1,
+            VerificationType::SignedCommits => 100,
+            VerificationType::LanguageBytes => 1_000_000_000,
+            _ => 10000,
+        }
+    }
+
+    /// Lower bounds of the ranges attested in bucketed disclosure, in
+    /// ascending order. Yes/no criteria have no buckets.
+    pub fn buckets(&self) -> &'static [u32] {
+        match self {
+            VerificationType::YearlyCommits => &[100, 365, 1000, 2500],
+            VerificationType::ConsecutiveDays => &[7, 30, 100, 365],
+            VerificationType::TotalStars => &[100, 500, 1000, 5000, 10000],
+            VerificationType::PublicRepos => &[5, 10, 25, 50, 100],
+            VerificationType::MergedPullRequests => &[10, 50, 100, 500],
+            VerificationType::OpenedIssues => &[10, 50, 100, 500],
+            VerificationType::CodeReviews => &[10, 50, 100, 500],
+            VerificationType::AccountAgeDays => &[365, 730, 1825, 3650],
+            VerificationType::Followers => &[10, 100, 500, 1000, 10000],
+            VerificationType::RepoCommits => &[10, 50, 100, 500],
+            VerificationType::LanguageRepos => &[1, 5, 10, 25],
+            VerificationType::LanguageBytes => &[10_000, 100_000, 1_000_000, 10_000_000],
+            VerificationType::SignedCommits => &[50, 80, 90, 100],
+            VerificationType::OrgMembership | VerificationType::RepoPermission => &[],
+        }
+    }
+
+    /// The highest bucket `value` reaches.
+    pub fn bucket_for(&self, value: u32) -> Option<u32> {
+        self.buckets()
+            .iter()
+            .rev()
+            .copied()
+            .find(|&bucket| value >= bucket)
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct VerificationResult {
     pub username: String,
     pub verification_type: VerificationType,
+    /// In bucketed disclosure, the lower bound of the bucket reached
     pub threshold: u32,
+    #[serde(default, skip_serializing_if = "CriteriaOptions::is_default")]
+    pub options: CriteriaOptions,
+    #[serde(default, skip_serializing_if = "DisclosureMode::is_default")]
+    pub disclosure: DisclosureMode,
     pub meets_criteria: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<ActivityHistogram>,
+    /// The operator's policy for anomalies, on commit-based criteria
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomaly_policy: Option<AnomalyPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub attestation_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attestation_claims: Option<serde_json::Value>,
+    /// The verifier's nonce, echoed back
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
     pub verified_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proof_hash: Option<String>,
+    /// Position of the proof hash in the transparency log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_index: Option<u64>,
+    /// Set once the proof is stored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ProofStatus>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofState {
+    Active,
+    Revoked,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Revoker {
+    Subject,
+    Operator,
+}
+
+/// Revocation status of a stored proof. Offline verifiers look up bit
+/// `status_list_index` of the status list at `status_list_credential`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofStatus {
+    pub state: ProofState,
+    pub status_list_index: u32,
+    pub status_list_credential: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_by: Option<Revoker>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RevocationRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// What an erasure request deleted, and what it couldn't. `receipt_hash`
+/// covers every other field and is bound to an enclave attestation token
+/// like a proof hash.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletionReceipt {
+    pub username: String,
+    pub proof_hashes: Vec<String>,
+    pub revoked_status_indexes: Vec<u32>,
+    pub not_purged: Vec<&'static str>,
+    pub cached_responses_deleted: usize,
+    pub webhook_deliveries_deleted: usize,
+    pub deleted_at: DateTime<Utc>,
+    pub receipt_hash: String,
+    pub attestation_token: String,
 }
 
 #[derive(Debug, Serialize)]
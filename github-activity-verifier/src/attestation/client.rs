@@ -5,6 +5,8 @@ use std::time::Duration;
 use thiserror::Error;
 use tracing::{debug, error, info};
 
+use crate::utils::status_list::StatusListEntry;
+
 #[derive(Error, Debug)]
 pub enum MAAError {
     #[error("SKR Sidecar not available: {0}")]
@@ -55,16 +57,21 @@ impl MAAClient {
         }
     }
 
-    pub async fn get_attestation_token(&self, runtime_data: &str) -> Result<String, MAAError> {
+    /// Requests a token whose runtime data binds `runtime_data` and, when
+    /// given, a verifier's `nonce` and a proof's status list entry.
+    pub async fn get_attestation_token(
+        &self,
+        runtime_data: &str,
+        nonce: Option<&str>,
+        status_entry: Option<StatusListEntry>,
+    ) -> Result<String, MAAError> {
         if self.endpoint.is_empty() {
             return Err(MAAError::EndpointNotConfigured);
         }
 
         info!("Requesting MAA attestation token");
 
-        let runtime_data_json = serde_json::json!({
-            "proof_data_hash": runtime_data
-        });
+        let runtime_data_json = runtime_data_claims(runtime_data, nonce, status_entry);
 
         let runtime_data_base64 =
             general_purpose::STANDARD.encode(runtime_data_json.to_string().as_bytes());
@@ -155,42 +162,66 @@ impl MAAClient {
     }
 
     pub fn parse_jwt_claims(&self, token: &str) -> Result<serde_json::Value, MAAError> {
-        // Split JWT into parts
-        let parts: Vec<&str> = token.split('.').collect();
-        if parts.len() != 3 {
-            return Err(MAAError::InvalidToken(format!(
-                "Invalid JWT format: expected 3 parts, got {}",
-                parts.len()
-            )));
-        }
+        parse_jwt_claims(token)
+    }
+}
 
-        // Decode the payload (second part)
-        let payload_part = parts[1];
+/// The runtime data sent with an attestation request, which MAA returns in
+/// the token's `x-ms-runtime` claim.
+pub fn runtime_data_claims(
+    runtime_data: &str,
+    nonce: Option<&str>,
+    status_entry: Option<StatusListEntry>,
+) -> serde_json::Value {
+    let mut claims = serde_json::json!({
+        "proof_data_hash": runtime_data
+    });
+    if let Some(nonce) = nonce {
+        claims["nonce"] = serde_json::Value::String(nonce.to_string());
+    }
+    if let Some(status_entry) = status_entry {
+        claims["status_list_index"] = status_entry.status_list_index.into();
+        claims["status_list_credential"] = status_entry.status_list_credential.into();
+    }
+    claims
+}
 
-        // JWT uses base64url encoding, add padding if needed
-        let payload_padded = self.add_base64_padding(payload_part);
+/// Decodes the claims of a JWT without checking its signature.
+pub fn parse_jwt_claims(token: &str) -> Result<serde_json::Value, MAAError> {
+    // Split JWT into parts
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err(MAAError::InvalidToken(format!(
+            "Invalid JWT format: expected 3 parts, got {}",
+            parts.len()
+        )));
+    }
 
-        let payload_bytes = general_purpose::URL_SAFE_NO_PAD
-            .decode(&payload_padded)
-            .or_else(|_| general_purpose::STANDARD.decode(&payload_padded))
-            .map_err(|e| MAAError::Base64Error(e))?;
+    // Decode the payload (second part)
+    let payload_part = parts[1];
 
-        let payload_str = String::from_utf8(payload_bytes)
-            .map_err(|e| MAAError::InvalidToken(format!("Invalid UTF-8 in JWT payload: {}", e)))?;
+    // JWT uses unpadded base64url encoding; padded standard base64 is
+    // accepted as well
+    let payload_bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload_part)
+        .or_else(|_| general_purpose::STANDARD.decode(add_base64_padding(payload_part)))
+        .map_err(MAAError::Base64Error)?;
 
-        let claims: serde_json::Value = serde_json::from_str(&payload_str)?;
+    let payload_str = String::from_utf8(payload_bytes)
+        .map_err(|e| MAAError::InvalidToken(format!("Invalid UTF-8 in JWT payload: {}", e)))?;
 
-        Ok(claims)
-    }
+    let claims: serde_json::Value = serde_json::from_str(&payload_str)?;
 
-    // Helper function to add padding to base64 strings if needed
-    fn add_base64_padding(&self, input: &str) -> String {
-        let mut padded = input.to_string();
-        while padded.len() % 4 != 0 {
-            padded.push('=');
-        }
-        padded
+    Ok(claims)
+}
+
+// Helper function to add padding to base64 strings if needed
+fn add_base64_padding(input: &str) -> String {
+    let mut padded = input.to_string();
+    while !padded.len().is_multiple_of(4) {
+        padded.push('=');
     }
+    padded
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
 pub mod client;
+pub mod offline;
 
 pub use client::{MAAClient, MAAError};
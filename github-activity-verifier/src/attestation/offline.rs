@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::api::types::{CriteriaOptions, DisclosureMode, VerificationType};
+use crate::attestation::client::parse_jwt_claims;
+use crate::attestation::MAAError;
+use crate::verification::anomaly::AnomalyPolicy;
+use crate::verification::histogram::ActivityHistogram;
+use crate::verification::proof::ProofData;
+
+#[derive(Debug, Error)]
+pub enum ProofCheckError {
+    #[error("The proof has no attestation token")]
+    MissingToken,
+
+    #[error("The proof was not attested: {0}")]
+    NotAttested(String),
+
+    #[error("Invalid attestation token: {0}")]
+    InvalidToken(#[from] MAAError),
+
+    #[error("Malformed proof: {0}")]
+    Malformed(String),
+
+    #[error("{0}")]
+    Mismatch(String),
+}
+
+/// The fields of a proof as returned by `/api/verify` that its hash covers.
+#[derive(Debug, Deserialize)]
+struct HashedFields {
+    username: String,
+    verification_type: VerificationType,
+    threshold: u32,
+    #[serde(default)]
+    options: CriteriaOptions,
+    #[serde(default)]
+    disclosure: DisclosureMode,
+    meets_criteria: bool,
+    verified_at: DateTime<Utc>,
+    histogram: Option<ActivityHistogram>,
+    anomaly_policy: Option<AnomalyPolicy>,
+    status: Option<HashedStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HashedStatus {
+    status_list_index: u32,
+    status_list_credential: String,
+}
+
+/// Recomputes the proof hash from the proof's own fields.
+fn recompute_hash(proof: &Value) -> Result<String, ProofCheckError> {
+    let fields = HashedFields::deserialize(proof)
+        .map_err(|err| ProofCheckError::Malformed(err.to_string()))?;
+    // Every issued proof has a status list entry, and its hash covers it
+    let status = fields.status.ok_or_else(|| {
+        ProofCheckError::Mismatch("The proof has no status list entry".to_string())
+    })?;
+    Ok(ProofData {
+        username: &fields.username,
+        verification_type: fields.verification_type,
+        options: &fields.options,
+        disclosure: fields.disclosure,
+        threshold: fields.threshold,
+        meets_criteria: fields.meets_criteria,
+        verified_at: fields.verified_at,
+        histogram: fields.histogram.as_ref(),
+        anomaly_policy: fields.anomaly_policy,
+        status_list_index: status.status_list_index,
+        status_list_credential: &status.status_list_credential,
+    }
+    .hash())
+}
+
+/// Checks a proof as returned by `/api/verify` without contacting the
+/// verifier: the proof hash must match the proof's own fields, and the
+/// token's `x-ms-runtime` claim must bind that hash, the proof's status list
+/// entry and, when `expected_nonce` is given, that nonce, which the proof
+/// must echo.
+///
+/// The token's signature is not checked here; verify it against the MAA
+/// instance's signing keys (`/certs`) as well.
+pub fn check_proof(proof: &Value, expected_nonce: Option<&str>) -> Result<(), ProofCheckError> {
+    let token = proof["attestation_token"]
+        .as_str()
+        .ok_or(ProofCheckError::MissingToken)?;
+    if matches!(token, "MAA_UNAVAILABLE" | "MAA_NOT_CONFIGURED") {
+        return Err(ProofCheckError::NotAttested(token.to_string()));
+    }
+
+    let claims = parse_jwt_claims(token)?;
+    let runtime = &claims["x-ms-runtime"];
+    let proof_hash = proof["proof_hash"].as_str();
+    if proof_hash.is_none() || runtime["proof_data_hash"].as_str() != proof_hash {
+        return Err(ProofCheckError::Mismatch(
+            "The token doesn't attest this proof hash".to_string(),
+        ));
+    }
+
+    // Otherwise an attested hash could be put on a relabelled proof
+    if Some(recompute_hash(proof)?.as_str()) != proof_hash {
+        return Err(ProofCheckError::Mismatch(
+            "The proof hash doesn't match the proof's fields".to_string(),
+        ));
+    }
+
+    // Otherwise a revoked proof could point offline verifiers at an unset bit
+    let status = &proof["status"];
+    if runtime["status_list_index"] != status["status_list_index"]
+        || runtime["status_list_credential"] != status["status_list_credential"]
+    {
+        return Err(ProofCheckError::Mismatch(
+            "The token doesn't attest this status list entry".to_string(),
+        ));
+    }
+
+    if let Some(expected_nonce) = expected_nonce {
+        if runtime["nonce"].as_str() != Some(expected_nonce) {
+            return Err(ProofCheckError::Mismatch(
+                "The token wasn't issued for this nonce".to_string(),
+            ));
+        }
+        if proof["nonce"].as_str() != Some(expected_nonce) {
+            return Err(ProofCheckError::Mismatch(
+                "The proof doesn't echo this nonce".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attestation::client::runtime_data_claims;
+    use crate::utils::status_list::StatusListEntry;
+    use base64::{engine::general_purpose, Engine as _};
+    use serde_json::json;
+
+    fn token(runtime: Value) -> Value {
+        let payload = general_purpose::URL_SAFE_NO_PAD
+            .encode(json!({ "x-ms-runtime": runtime }).to_string().as_bytes());
+        json!(format!("eyJhbGciOiJSUzI1NiJ9.{}.signature", payload))
+    }
+
+    /// A proof as `/api/verify` returns it, attested for its hash.
+    fn proof(nonce: Option<&str>) -> Value {
+        let mut proof = json!({
+            "username": "octocat",
+            "verification_type": "yearly_commits",
+            "threshold": 365,
+            "options": {"repos": ["octocat/Hello-World"]},
+            "meets_criteria": true,
+            "anomaly_policy": "report",
+            "nonce": nonce,
+            "verified_at": "2026-03-01T12:00:00Z",
+            "status": {
+                "state": "active",
+                "status_list_index": 7,
+                "status_list_credential": "/api/status-list",
+            },
+        });
+        let hash = recompute_hash(&proof).unwrap();
+        proof["attestation_token"] = token(runtime_data_claims(
+            &hash,
+            nonce,
+            Some(StatusListEntry::new(7)),
+        ));
+        proof["proof_hash"] = json!(hash);
+        proof
+    }
+
+    #[test]
+    fn test_check_proof() {
+        let nonce = "n0nce-from-the-verifier";
+        let attested = proof(Some(nonce));
+        let hash = attested["proof_hash"].as_str().unwrap().to_string();
+        let entry = Some(StatusListEntry::new(7));
+        assert!(check_proof(&attested, Some(nonce)).is_ok());
+        assert!(check_proof(&attested, None).is_ok());
+        assert!(matches!(
+            check_proof(&attested, Some("another-verifier-nonce")),
+            Err(ProofCheckError::Mismatch(_))
+        ));
+
+        // A token made without the nonce can't be replayed to a verifier
+        let mut replayed = attested.clone();
+        replayed["attestation_token"] = token(runtime_data_claims(&hash, None, entry));
+        assert!(check_proof(&replayed, Some(nonce)).is_err());
+
+        let mut other_hash = attested.clone();
+        other_hash["proof_hash"] = json!("b".repeat(64));
+        assert!(check_proof(&other_hash, None).is_err());
+        other_hash["attestation_token"] = token(runtime_data_claims(&"b".repeat(64), None, entry));
+        assert!(check_proof(&other_hash, None).is_err());
+
+        // The status list index can't be swapped for another bit
+        let mut swapped = attested.clone();
+        swapped["status"]["status_list_index"] = json!(8);
+        assert!(check_proof(&swapped, None).is_err());
+
+        let mut unbound = attested.clone();
+        unbound["attestation_token"] = token(runtime_data_claims(&hash, None, None));
+        assert!(check_proof(&unbound, None).is_err());
+
+        let unattested = json!({ "attestation_token": "MAA_UNAVAILABLE", "proof_hash": hash });
+        assert!(matches!(
+            check_proof(&unattested, None),
+            Err(ProofCheckError::NotAttested(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_proof_rejects_relabelled_proofs() {
+        let attested = proof(None);
+        for (field, value) in [
+            ("username", json!("hubot")),
+            ("verification_type", json!("consecutive_days")),
+            ("meets_criteria", json!(false)),
+            ("verified_at", json!("2026-03-02T12:00:00Z")),
+            ("options", json!({})),
+            ("anomaly_policy", json!("off")),
+        ] {
+            let mut relabelled = attested.clone();
+            relabelled[field] = value;
+            assert!(
+                matches!(
+                    check_proof(&relabelled, None),
+                    Err(ProofCheckError::Mismatch(_))
+                ),
+                "{} was not bound",
+                field
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_proof_requires_status() {
+        // Dropping the status would skip the revocation check
+        let mut without_status = proof(None);
+        without_status.as_object_mut().unwrap().remove("status");
+        assert!(matches!(
+            check_proof(&without_status, None),
+            Err(ProofCheckError::Mismatch(_))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+pub mod oauth;
+pub mod session;
+
+pub use oauth::{GitHubOAuth, OAuthError, LOGIN_STATE_COOKIE};
+pub use session::{SessionStore, SESSION_COOKIE};
+
+use actix_web::HttpRequest;
+use sha2::{Digest, Sha256};
+
+use crate::utils::errors::AppError;
+
+/// Who is acting on a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Principal {
+    /// A GitHub user signed in through OAuth
+    Subject(String),
+    /// The operator, holding `OPERATOR_API_KEY`
+    Operator,
+}
+
+impl Principal {
+    /// Operators may act on every user; subjects only on themselves.
+    pub fn may_act_for(&self, username: &str) -> bool {
+        match self {
+            Principal::Operator => true,
+            Principal::Subject(login) => login.eq_ignore_ascii_case(username),
+        }
+    }
+}
+
+pub struct Authenticator {
+    pub oauth: GitHubOAuth,
+    pub sessions: SessionStore,
+    operator_key: Option<String>,
+}
+
+impl Authenticator {
+    pub fn new(oauth: GitHubOAuth, operator_key: Option<String>) -> Self {
+        Self {
+            oauth,
+            sessions: SessionStore::new(),
+            operator_key,
+        }
+    }
+
+    /// Identifies the caller from an `Authorization: Bearer` operator key or
+    /// a session cookie.
+    pub fn authenticate(&self, req: &HttpRequest) -> Option<Principal> {
+        let bearer = req
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if let (Some(bearer), Some(operator_key)) = (bearer, &self.operator_key) {
+            // Comparing digests keeps the comparison time independent of
+            // how much of the key matched
+            if Sha256::digest(bearer.as_bytes()) == Sha256::digest(operator_key.as_bytes()) {
+                return Some(Principal::Operator);
+            }
+        }
+
+        let cookie = req.cookie(SESSION_COOKIE)?;
+        self.sessions.login(cookie.value()).map(Principal::Subject)
+    }
+
+    /// Requires a caller allowed to act for `username`.
+    pub fn require(&self, req: &HttpRequest, username: &str) -> Result<Principal, AppError> {
+        let principal = self.authenticate(req).ok_or_else(|| {
+            AppError::Unauthorized("Sign in with GitHub or provide the operator key".to_string())
+        })?;
+        if !principal.may_act_for(username) {
+            return Err(AppError::Forbidden(format!(
+                "Only {} or the operator may do this",
+                username
+            )));
+        }
+        Ok(principal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::cookie::Cookie;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_authenticate() {
+        let auth = Authenticator::new(GitHubOAuth::new(None, None), Some("op-key".to_string()));
+        let session = auth.sessions.create("Octocat");
+
+        let operator = TestRequest::default()
+            .insert_header(("Authorization", "Bearer op-key"))
+            .to_http_request();
+        assert_eq!(auth.authenticate(&operator), Some(Principal::Operator));
+        assert!(auth.require(&operator, "anyone").is_ok());
+
+        let wrong_key = TestRequest::default()
+            .insert_header(("Authorization", "Bearer guess"))
+            .to_http_request();
+        assert_eq!(auth.authenticate(&wrong_key), None);
+
+        let subject = TestRequest::default()
+            .cookie(Cookie::new(SESSION_COOKIE, session))
+            .to_http_request();
+        assert!(auth.require(&subject, "octocat").is_ok());
+        assert!(matches!(
+            auth.require(&subject, "hubot"),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            auth.require(&TestRequest::default().to_http_request(), "octocat"),
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+use tracing::{debug, info};
+
+const AUTHORIZE_URL: &str = "https://github.com/login/oauth/authorize";
+const ACCESS_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const USER_URL: &str = "https://api.github.com/user";
+// How long a login may take between the redirect and the callback
+const LOGIN_TIMEOUT_MINUTES: i64 = 10;
+
+/// Holds the state of the login started in this browser, so a callback
+/// carrying someone else's state (login CSRF) is refused.
+pub const LOGIN_STATE_COOKIE: &str = "verifier_login_state";
+
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("GitHub OAuth is not configured")]
+    NotConfigured,
+
+    #[error("Unknown or expired login state")]
+    InvalidState,
+
+    #[error("GitHub rejected the authorization: {0}")]
+    Rejected(String),
+
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+}
+
+#[derive(Debug, Deserialize)]
+struct AccessTokenResponse {
+    access_token: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthenticatedUser {
+    login: String,
+}
+
+#[derive(Debug, Clone)]
+struct PendingLogin {
+    return_to: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// GitHub's web application flow, used to prove that a visitor controls the
+/// GitHub account a proof was issued for.
+#[derive(Clone)]
+pub struct GitHubOAuth {
+    client: Client,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    pending: Arc<RwLock<HashMap<String, PendingLogin>>>,
+}
+
+impl GitHubOAuth {
+    pub fn new(client_id: Option<String>, client_secret: Option<String>) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent("GitHub-Activity-Verifier/1.0")
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            client_id,
+            client_secret,
+            pending: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.client_id.is_some() && self.client_secret.is_some()
+    }
+
+    /// How long a started login stays valid.
+    pub fn login_timeout(&self) -> Duration {
+        Duration::minutes(LOGIN_TIMEOUT_MINUTES)
+    }
+
+    /// Starts a login and returns the GitHub URL to redirect to, together
+    /// with the login state to store in the visitor's `LOGIN_STATE_COOKIE`.
+    /// The visitor is sent back to `return_to` once the login completes.
+    pub fn authorize_url(&self, return_to: String) -> Result<(String, String), OAuthError> {
+        let client_id = self.client_id.as_ref().ok_or(OAuthError::NotConfigured)?;
+        let state = uuid::Uuid::new_v4().simple().to_string();
+
+        {
+            let now = Utc::now();
+            let mut pending = self.pending.write().unwrap();
+            pending.retain(|_, login| login.expires_at > now);
+            pending.insert(
+                state.clone(),
+                PendingLogin {
+                    return_to,
+                    expires_at: now + self.login_timeout(),
+                },
+            );
+        }
+
+        let url = Url::parse_with_params(
+            AUTHORIZE_URL,
+            &[
+                ("client_id", client_id.as_str()),
+                ("state", state.as_str()),
+                ("allow_signup", "false"),
+            ],
+        )
+        .expect("The authorize URL is valid");
+        Ok((url.to_string(), state))
+    }
+
+    /// Finishes a login: checks `state` against the one stored in the
+    /// browser, exchanges `code` for a token and returns the authenticated
+    /// login together with the return path.
+    pub async fn complete(
+        &self,
+        code: &str,
+        state: &str,
+        browser_state: Option<&str>,
+    ) -> Result<(String, String), OAuthError> {
+        let (Some(client_id), Some(client_secret)) = (&self.client_id, &self.client_secret) else {
+            return Err(OAuthError::NotConfigured);
+        };
+        if browser_state != Some(state) {
+            return Err(OAuthError::InvalidState);
+        }
+
+        let pending = self
+            .pending
+            .write()
+            .unwrap()
+            .remove(state)
+            .filter(|login| login.expires_at > Utc::now())
+            .ok_or(OAuthError::InvalidState)?;
+
+        let token: AccessTokenResponse = self
+            .client
+            .post(ACCESS_TOKEN_URL)
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", client_id.as_str()),
+                ("client_secret", client_secret.as_str()),
+                ("code", code),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+        let access_token = token.access_token.ok_or_else(|| {
+            OAuthError::Rejected(
+                token
+                    .error_description
+                    .unwrap_or_else(|| "no access token returned".to_string()),
+            )
+        })?;
+
+        let response = self
+            .client
+            .get(USER_URL)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header("Accept", "application/vnd.github.v3+json")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(OAuthError::Rejected(format!(
+                "user lookup failed with status {}",
+                response.status()
+            )));
+        }
+        let user: AuthenticatedUser = response.json().await?;
+
+        debug!("OAuth login state {} completed", state);
+        info!("GitHub user {} signed in", user.login);
+        Ok((user.login, pending.return_to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_url() {
+        let oauth = GitHubOAuth::new(Some("client-123".to_string()), Some("secret".to_string()));
+        let (url, state) = oauth.authorize_url("/?proof=abc".to_string()).unwrap();
+        let url = Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.host_str(), Some("github.com"));
+        assert_eq!(params["client_id"], "client-123");
+        assert_eq!(params["state"], state);
+        let pending = oauth.pending.read().unwrap();
+        assert_eq!(pending[&params["state"]].return_to, "/?proof=abc");
+
+        let disabled = GitHubOAuth::new(None, None);
+        assert!(!disabled.is_enabled());
+        assert!(matches!(
+            disabled.authorize_url("/".to_string()),
+            Err(OAuthError::NotConfigured)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_complete_requires_browser_state() {
+        let oauth = GitHubOAuth::new(Some("client-123".to_string()), Some("secret".to_string()));
+        let (_, state) = oauth.authorize_url("/".to_string()).unwrap();
+        let (_, other_state) = oauth.authorize_url("/".to_string()).unwrap();
+
+        // A callback started in another browser is refused
+        for browser_state in [None, Some(other_state.as_str())] {
+            assert!(matches!(
+                oauth.complete("code", &state, browser_state).await,
+                Err(OAuthError::InvalidState)
+            ));
+        }
+        assert!(oauth.pending.read().unwrap().contains_key(&state));
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::debug;
+
+pub const SESSION_COOKIE: &str = "verifier_session";
+const SESSION_HOURS: i64 = 12;
+
+#[derive(Debug, Clone)]
+struct Session {
+    login: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// In-memory sessions of users who signed in with GitHub, keyed by a random
+/// session id kept in the `verifier_session` cookie.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStore {
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_age(&self) -> Duration {
+        Duration::hours(SESSION_HOURS)
+    }
+
+    /// Creates a session for `login` and returns its id.
+    pub fn create(&self, login: &str) -> String {
+        let id = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let now = Utc::now();
+
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(
+            id.clone(),
+            Session {
+                login: login.to_string(),
+                expires_at: now + self.max_age(),
+            },
+        );
+        debug!("Created session for {}", login);
+        id
+    }
+
+    /// The login of a live session.
+    pub fn login(&self, id: &str) -> Option<String> {
+        let sessions = self.sessions.read().unwrap();
+        sessions
+            .get(id)
+            .filter(|session| session.expires_at > Utc::now())
+            .map(|session| session.login.clone())
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.sessions.write().unwrap().remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions() {
+        let store = SessionStore::new();
+        let id = store.create("octocat");
+        assert_eq!(id.len(), 64);
+        assert_eq!(store.login(&id).as_deref(), Some("octocat"));
+        assert_eq!(store.login("unknown"), None);
+
+        store.remove(&id);
+        assert_eq!(store.login(&id), None);
+    }
+}
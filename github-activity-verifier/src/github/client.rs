@@ -1,19 +1,55 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use reqwest::{
     header::{HeaderMap, HeaderValue, USER_AGENT},
     Client,
 };
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
-use crate::github::{GitHubError, GitHubEvent, GitHubUser, GitHubUserRepo};
+use crate::github::{
+    GitHubCollaboratorPermission, GitHubCommit, GitHubDataSource, GitHubError, GitHubEvent,
+    GitHubIssue, GitHubReviewContribution, GitHubReviewContributions, GitHubSearchPage, GitHubUser,
+    GitHubUserRepo, GraphQLResponse, GraphQLUserQuery, ReviewContributionsUser,
+};
+use crate::utils::progress::{ProgressReporter, VerificationPhase};
 
 const GITHUB_API_BASE: &str = "https://api.github.com";
+const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+const REVIEW_CONTRIBUTIONS_QUERY: &str = r#"
+query($login: String!, $from: DateTime!, $to: DateTime!, $cursor: String) {
+  user(login: $login) {
+    contributionsCollection(from: $from, to: $to) {
+      pullRequestReviewContributions(first: 100, after: $cursor) {
+        pageInfo {
+          hasNextPage
+          endCursor
+        }
+        nodes {
+          occurredAt
+          pullRequestReview {
+            state
+          }
+          repository {
+            nameWithOwner
+          }
+        }
+      }
+    }
+  }
+}
+"#;
 const EVENTS_PER_PAGE: u8 = 100;
 const MAX_PAGES: u8 = 3;
+const MAX_GRAPHQL_PAGES: u32 = 10;
+pub const COMMITS_PER_PAGE: u32 = 100;
 
 pub struct GitHubClient {
     client: Client,
-    token: Option<String>,
+    authenticated: bool,
 }
 
 impl GitHubClient {
@@ -45,10 +81,120 @@ impl GitHubClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, token }
+        Self {
+            client,
+            authenticated: token.is_some(),
+        }
+    }
+}
+
+/// Maps the error statuses shared by the REST endpoints: 404 means the user
+/// doesn't exist, 403 with no remaining quota means we are rate limited.
+async fn check_response(
+    response: reqwest::Response,
+    username: &str,
+) -> Result<reqwest::Response, GitHubError> {
+    let status = response.status();
+
+    if status == 404 {
+        return Err(GitHubError::UserNotFound(username.to_string()));
+    }
+
+    if status == 403 {
+        // Check if it's rate limiting
+        if let Some(rate_limit) = response.headers().get("X-RateLimit-Remaining") {
+            if rate_limit == "0" {
+                return Err(GitHubError::RateLimit);
+            }
+        }
+        return Err(GitHubError::ApiError {
+            status: status.as_u16(),
+            message: "Forbidden - check API token permissions".to_string(),
+        });
+    }
+
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(GitHubError::ApiError {
+            status: status.as_u16(),
+            message: error_text,
+        });
+    }
+
+    Ok(response)
+}
+
+impl GitHubClient {
+    /// Runs a GraphQL query shaped `{ user(login: $login) { ... } }` and
+    /// returns the user node.
+    async fn query_user<T: DeserializeOwned>(
+        &self,
+        username: &str,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T, GitHubError> {
+        if !self.authenticated {
+            return Err(GitHubError::TokenRequired(
+                "The GitHub GraphQL API".to_string(),
+            ));
+        }
+
+        let request = serde_json::json!({
+            "query": query,
+            "variables": variables,
+        });
+
+        let response = self
+            .client
+            .post(GITHUB_GRAPHQL_URL)
+            .json(&request)
+            .send()
+            .await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(GitHubError::ApiError {
+                status: status.as_u16(),
+                message: error_text,
+            });
+        }
+
+        let response: GraphQLResponse<GraphQLUserQuery<T>> = response.json().await?;
+        match response.data.and_then(|data| data.user) {
+            Some(user) => Ok(user),
+            // GitHub reports an unknown login as a NOT_FOUND error with a null user
+            None if response
+                .errors
+                .iter()
+                .all(|error| error.error_type.as_deref() == Some("NOT_FOUND")) =>
+            {
+                Err(GitHubError::UserNotFound(username.to_string()))
+            }
+            None => Err(GitHubError::ApiError {
+                status: status.as_u16(),
+                message: response
+                    .errors
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHubDataSource for GitHubClient {
+    fn name(&self) -> &'static str {
+        "live"
     }
 
-    pub async fn fetch_user_events(&self, username: &str) -> Result<Vec<GitHubEvent>, GitHubError> {
+    async fn fetch_user_events(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<GitHubEvent>, GitHubError> {
         let mut all_events = Vec::new();
 
         for page in 1..=MAX_PAGES {
@@ -60,33 +206,7 @@ impl GitHubClient {
             debug!("Fetching GitHub events: {}", url);
 
             let response = self.client.get(&url).send().await?;
-            let status = response.status();
-
-            if status == 404 {
-                return Err(GitHubError::UserNotFound(username.to_string()));
-            }
-
-            if status == 403 {
-                // Check if it's rate limiting
-                if let Some(rate_limit) = response.headers().get("X-RateLimit-Remaining") {
-                    if rate_limit == "0" {
-                        return Err(GitHubError::RateLimit);
-                    }
-                }
-                return Err(GitHubError::ApiError {
-                    status: status.as_u16(),
-                    message: "Forbidden - check API token permissions".to_string(),
-                });
-            }
-
-            if !status.is_success() {
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(GitHubError::ApiError {
-                    status: status.as_u16(),
-                    message: error_text,
-                });
-            }
-
+            let response = check_response(response, username).await?;
             let events: Vec<GitHubEvent> = response.json().await?;
 
             if events.is_empty() {
@@ -119,6 +239,11 @@ impl GitHubClient {
             }
 
             all_events.extend(events);
+            progress.report(
+                VerificationPhase::FetchingEvents,
+                format!("Fetched events page {} of {}", page, MAX_PAGES),
+                Some(all_events.len() as u32),
+            );
         }
 
         info!(
@@ -129,32 +254,20 @@ impl GitHubClient {
         Ok(all_events)
     }
 
-    pub async fn fetch_user(&self, username: &str) -> Result<GitHubUser, GitHubError> {
+    async fn fetch_user(&self, username: &str) -> Result<GitHubUser, GitHubError> {
         let url = format!("{}/users/{}", GITHUB_API_BASE, username);
 
         debug!("Fetching GitHub user: {}", url);
 
         let response = self.client.get(&url).send().await?;
-        let status = response.status();
-
-        if status == 404 {
-            return Err(GitHubError::UserNotFound(username.to_string()));
-        }
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(GitHubError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
-        }
+        let response = check_response(response, username).await?;
 
         let user: GitHubUser = response.json().await?;
         debug!("Fetched user info for: {}", username);
         Ok(user)
     }
 
-    pub async fn fetch_user_repos(
+    async fn fetch_user_repos(
         &self,
         username: &str,
         page: u32,
@@ -167,60 +280,303 @@ impl GitHubClient {
         debug!("Fetching GitHub repos: {}", url);
 
         let response = self.client.get(&url).send().await?;
-        let status = response.status();
-
-        if status == 404 {
-            return Err(GitHubError::UserNotFound(username.to_string()));
-        }
-
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(GitHubError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
-        }
+        let response = check_response(response, username).await?;
 
         let repos: Vec<GitHubUserRepo> = response.json().await?;
         debug!("Fetched {} repos from page {}", repos.len(), page);
         Ok(repos)
     }
 
-    pub async fn count_total_stars(&self, username: &str) -> Result<u32, GitHubError> {
-        let mut total_stars = 0;
-        let mut page = 1;
-
-        loop {
-            let repos = self.fetch_user_repos(username, page).await?;
-            if repos.is_empty() {
-                break;
-            }
-
-            total_stars += repos.iter().map(|repo| repo.stargazers_count).sum::<u32>();
-            page += 1;
+    async fn fetch_review_contributions(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<GitHubReviewContributions, GitHubError> {
+        let mut reviews = Vec::new();
+        let mut truncated = false;
+
+        // contributionsCollection only accepts ranges of up to one year
+        let mut chunk_start = from;
+        while chunk_start < to {
+            let chunk_end = (chunk_start + chrono::Duration::days(365)).min(to);
+            let mut cursor: Option<String> = None;
+
+            for page in 1..=MAX_GRAPHQL_PAGES {
+                debug!(
+                    "Fetching review contributions for {} between {} and {} (page {})",
+                    username, chunk_start, chunk_end, page
+                );
 
-            // Limit to 10 pages (1000 repos) to prevent excessive API calls
-            if page > 10 {
-                warn!(
-                    "User {} has more than 1000 repos, limiting star count calculation",
-                    username
+                let user: ReviewContributionsUser = self
+                    .query_user(
+                        username,
+                        REVIEW_CONTRIBUTIONS_QUERY,
+                        serde_json::json!({
+                            "login": username,
+                            "from": chunk_start.to_rfc3339(),
+                            "to": chunk_end.to_rfc3339(),
+                            "cursor": cursor,
+                        }),
+                    )
+                    .await?;
+
+                let connection = user
+                    .contributions_collection
+                    .pull_request_review_contributions;
+                reviews.extend(
+                    connection
+                        .nodes
+                        .into_iter()
+                        .map(|node| GitHubReviewContribution {
+                            occurred_at: node.occurred_at,
+                            state: node.pull_request_review.state,
+                            repository: node.repository.name_with_owner,
+                        }),
                 );
-                break;
+
+                if !connection.page_info.has_next_page {
+                    break;
+                }
+                if page == MAX_GRAPHQL_PAGES {
+                    warn!(
+                        "Review contributions for {} between {} and {} exceed {} pages",
+                        username, chunk_start, chunk_end, MAX_GRAPHQL_PAGES
+                    );
+                    truncated = true;
+                }
+                cursor = connection.page_info.end_cursor;
             }
+
+            chunk_start = chunk_end;
         }
 
         info!(
-            "User {} has {} total stars across {} pages of repos",
+            "Fetched {} review contributions for user: {}",
+            reviews.len(),
+            username
+        );
+        Ok(GitHubReviewContributions { reviews, truncated })
+    }
+
+    async fn search_merged_pull_requests(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError> {
+        // Dates are inclusive in the search syntax; the engine re-checks the
+        // exact instants
+        let mut query = format!(
+            "is:pr is:merged author:{} merged:{}..{}",
+            username,
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d")
+        );
+        if exclude_own_repos {
+            query.push_str(&format!(" -user:{}", username));
+        }
+        // Multiple repo: qualifiers are OR'ed by the search API
+        for repo in repos {
+            query.push_str(&format!(" repo:{}", repo));
+        }
+
+        debug!("Searching GitHub pull requests: {} (page {})", query, page);
+
+        let response = self
+            .client
+            .get(format!("{}/search/issues", GITHUB_API_BASE))
+            .query(&[
+                ("q", query.as_str()),
+                ("per_page", "100"),
+                ("page", &page.to_string()),
+            ])
+            .send()
+            .await?;
+
+        // The search API answers 422 when the author doesn't exist
+        if response.status() == 422 {
+            return Err(GitHubError::UserNotFound(username.to_string()));
+        }
+
+        let response = check_response(response, username).await?;
+        let results: GitHubSearchPage<GitHubIssue> = response.json().await?;
+        debug!(
+            "Fetched {} of {} merged pull requests from page {}",
+            results.items.len(),
+            results.total_count,
+            page
+        );
+        Ok(results)
+    }
+
+    async fn search_opened_issues(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError> {
+        let mut query = format!(
+            "is:issue author:{} created:{}..{}",
             username,
-            total_stars,
-            page - 1
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d")
+        );
+        if exclude_own_repos {
+            query.push_str(&format!(" -user:{}", username));
+        }
+        for repo in repos {
+            query.push_str(&format!(" repo:{}", repo));
+        }
+
+        debug!("Searching GitHub issues: {} (page {})", query, page);
+
+        let response = self
+            .client
+            .get(format!("{}/search/issues", GITHUB_API_BASE))
+            .query(&[
+                ("q", query.as_str()),
+                ("per_page", "100"),
+                ("page", &page.to_string()),
+            ])
+            .send()
+            .await?;
+
+        if response.status() == 422 {
+            return Err(GitHubError::UserNotFound(username.to_string()));
+        }
+
+        let response = check_response(response, username).await?;
+        let results: GitHubSearchPage<GitHubIssue> = response.json().await?;
+        debug!(
+            "Fetched {} of {} opened issues from page {}",
+            results.items.len(),
+            results.total_count,
+            page
+        );
+        Ok(results)
+    }
+
+    async fn fetch_repo_commits(
+        &self,
+        repo: &str,
+        username: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        page: u32,
+    ) -> Result<Vec<GitHubCommit>, GitHubError> {
+        let url = format!("{}/repos/{}/commits", GITHUB_API_BASE, repo);
+        debug!(
+            "Fetching commits to {} by {} between {} and {} (page {})",
+            repo, username, since, until, page
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("author", username),
+                ("since", &since.to_rfc3339()),
+                ("until", &until.to_rfc3339()),
+                ("per_page", &COMMITS_PER_PAGE.to_string()),
+                ("page", &page.to_string()),
+            ])
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            404 => return Err(GitHubError::RepositoryNotFound(repo.to_string())),
+            // Empty repositories answer 409 Conflict
+            409 => return Ok(Vec::new()),
+            _ => {}
+        }
+
+        let response = check_response(response, username).await?;
+        let commits: Vec<GitHubCommit> = response.json().await?;
+        debug!(
+            "Fetched {} commits to {} from page {}",
+            commits.len(),
+            repo,
+            page
+        );
+        Ok(commits)
+    }
+
+    async fn fetch_repo_languages(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<BTreeMap<String, u64>, GitHubError> {
+        let url = format!("{}/repos/{}/languages", GITHUB_API_BASE, repo);
+        debug!("Fetching languages of {}", repo);
+
+        let response = self.client.get(&url).send().await?;
+
+        if response.status() == 404 {
+            return Err(GitHubError::RepositoryNotFound(repo.to_string()));
+        }
+
+        let response = check_response(response, username).await?;
+        Ok(response.json().await?)
+    }
+
+    async fn check_public_membership(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<bool, GitHubError> {
+        let url = format!(
+            "{}/orgs/{}/public_members/{}",
+            GITHUB_API_BASE, org, username
         );
-        Ok(total_stars)
+        debug!("Checking public membership of {} in {}", username, org);
+
+        let response = self.client.get(&url).send().await?;
+
+        // 204 for public members, 404 for everyone else
+        if response.status() == 404 {
+            return Ok(false);
+        }
+
+        let response = check_response(response, username).await?;
+        Ok(response.status() == 204)
     }
 
-    pub async fn count_public_repos(&self, username: &str) -> Result<u32, GitHubError> {
-        let user = self.fetch_user(username).await?;
-        info!("User {} has {} public repos", username, user.public_repos);
-        Ok(user.public_repos)
+    async fn fetch_collaborator_permission(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<GitHubCollaboratorPermission, GitHubError> {
+        if !self.authenticated {
+            return Err(GitHubError::TokenRequired(
+                "Repository permission checks".to_string(),
+            ));
+        }
+
+        let url = format!(
+            "{}/repos/{}/collaborators/{}/permission",
+            GITHUB_API_BASE, repo, username
+        );
+        debug!("Fetching permission of {} on {}", username, repo);
+
+        let response = self.client.get(&url).send().await?;
+
+        if response.status() == 404 {
+            return Err(GitHubError::RepositoryNotFound(repo.to_string()));
+        }
+
+        let response = check_response(response, username).await?;
+        let permission: GitHubCollaboratorPermission = response.json().await?;
+        debug!(
+            "User {} has role {} on {}",
+            username, permission.role_name, repo
+        );
+        Ok(permission)
     }
 }
@@ -0,0 +1,340 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::github::{
+    GitHubCollaboratorPermission, GitHubCommit, GitHubDataSource, GitHubError, GitHubEvent,
+    GitHubIssue, GitHubReviewContributions, GitHubSearchPage, GitHubUser, GitHubUserRepo,
+};
+use crate::utils::progress::{ProgressReporter, VerificationPhase};
+
+pub const USER_FILE: &str = "user.json";
+pub const EVENTS_FILE: &str = "events.json";
+pub const REVIEW_CONTRIBUTIONS_FILE: &str = "review_contributions.json";
+pub const LANGUAGES_FILE: &str = "languages.json";
+pub const ORG_MEMBERSHIPS_FILE: &str = "org_memberships.json";
+pub const REPO_PERMISSIONS_FILE: &str = "repo_permissions.json";
+
+pub fn repos_page_file(page: u32) -> String {
+    format!("repos_page_{}.json", page)
+}
+
+pub fn merged_pulls_page_file(page: u32) -> String {
+    format!("merged_pulls_page_{}.json", page)
+}
+
+pub fn opened_issues_page_file(page: u32) -> String {
+    format!("opened_issues_page_{}.json", page)
+}
+
+/// `owner/name` becomes `commits_owner__name_page_<n>.json`.
+pub fn repo_commits_page_file(repo: &str, page: u32) -> String {
+    format!(
+        "commits_{}_page_{}.json",
+        repo.to_ascii_lowercase().replace('/', "__"),
+        page
+    )
+}
+
+/// Short hash of a request's parameters. Recordings of the same endpoint
+/// with different parameters (e.g. commits of one repository over several
+/// ranges) are kept apart by it.
+pub fn request_key(params: &[String]) -> String {
+    let digest = Sha256::digest(params.join("\n").as_bytes());
+    format!("{:x}", digest)[..16].to_string()
+}
+
+/// `name.json` becomes `name.<key>.json`.
+pub fn keyed_file(file_name: &str, key: &str) -> String {
+    let stem = file_name.strip_suffix(".json").unwrap_or(file_name);
+    format!("{}.{}.json", stem, key)
+}
+
+pub fn range_key(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    request_key(&[from.to_rfc3339(), to.to_rfc3339()])
+}
+
+pub fn search_key(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    exclude_own_repos: bool,
+    repos: &[String],
+) -> String {
+    let mut params = vec![
+        from.to_rfc3339(),
+        to.to_rfc3339(),
+        exclude_own_repos.to_string(),
+    ];
+    params.extend(repos.iter().map(|repo| repo.to_ascii_lowercase()));
+    request_key(&params)
+}
+
+pub fn user_dir(root: &Path, username: &str) -> PathBuf {
+    root.join(username.to_ascii_lowercase())
+}
+
+/// Replays GitHub responses from a directory of JSON files:
+///
+/// ```text
+/// <root>/<username>/user.json
+/// <root>/<username>/events.json
+/// <root>/<username>/repos_page_<n>.json
+/// <root>/<username>/review_contributions.json
+/// <root>/<username>/merged_pulls_page_<n>.json
+/// <root>/<username>/opened_issues_page_<n>.json
+/// <root>/<username>/commits_<owner>__<repo>_page_<n>.json
+/// <root>/<username>/languages.json            { "<owner>/<repo>": { "Rust": 1234 } }
+/// <root>/<username>/org_memberships.json      { "<org>": true }
+/// <root>/<username>/repo_permissions.json     { "<owner>/<repo>": { ... } }
+/// ```
+///
+/// Organization and repository keys are lowercase.
+///
+/// Recordings of requests with a time range or search options are stored as
+/// `<file>.<key>.json`, where the key hashes those parameters. Replays load
+/// the file recorded for the same parameters and fall back to `<file>.json`,
+/// which is how hand-written fixtures are named.
+///
+/// A user without a directory does not exist. Missing files inside a user's
+/// directory are treated as empty responses, except for `user.json` and
+/// `review_contributions.json`: the latter is only recorded when a GitHub
+/// token was configured, so its absence replays as `TokenRequired`.
+pub struct FixtureDataSource {
+    root: PathBuf,
+}
+
+impl FixtureDataSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn load<T: DeserializeOwned>(
+        &self,
+        username: &str,
+        file_name: &str,
+    ) -> Result<Option<T>, GitHubError> {
+        let dir = user_dir(&self.root, username);
+        if !dir.is_dir() {
+            return Err(GitHubError::UserNotFound(username.to_string()));
+        }
+
+        let path = dir.join(file_name);
+        debug!("Loading GitHub fixture: {}", path.display());
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .map_err(|err| GitHubError::Fixture(format!("{}: {}", path.display(), err))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(GitHubError::Fixture(format!("{}: {}", path.display(), err))),
+        }
+    }
+
+    /// Loads the file recorded for the request parameters `key`, or the
+    /// unkeyed file when there is none.
+    fn load_keyed<T: DeserializeOwned>(
+        &self,
+        username: &str,
+        file_name: &str,
+        key: &str,
+    ) -> Result<Option<T>, GitHubError> {
+        match self.load(username, &keyed_file(file_name, key))? {
+            Some(value) => Ok(Some(value)),
+            None => self.load(username, file_name),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHubDataSource for FixtureDataSource {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    async fn fetch_user_events(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<GitHubEvent>, GitHubError> {
+        let events: Vec<GitHubEvent> = self.load(username, EVENTS_FILE)?.unwrap_or_default();
+        progress.report(
+            VerificationPhase::FetchingEvents,
+            "Loaded recorded events",
+            Some(events.len() as u32),
+        );
+        Ok(events)
+    }
+
+    async fn fetch_user(&self, username: &str) -> Result<GitHubUser, GitHubError> {
+        self.load(username, USER_FILE)?
+            .ok_or_else(|| GitHubError::UserNotFound(username.to_string()))
+    }
+
+    async fn fetch_user_repos(
+        &self,
+        username: &str,
+        page: u32,
+    ) -> Result<Vec<GitHubUserRepo>, GitHubError> {
+        Ok(self
+            .load(username, &repos_page_file(page))?
+            .unwrap_or_default())
+    }
+
+    async fn fetch_review_contributions(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<GitHubReviewContributions, GitHubError> {
+        self.load_keyed(username, REVIEW_CONTRIBUTIONS_FILE, &range_key(from, to))?
+            .ok_or_else(|| GitHubError::TokenRequired("Recorded review contributions".to_string()))
+    }
+
+    async fn search_merged_pull_requests(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError> {
+        Ok(self
+            .load_keyed(
+                username,
+                &merged_pulls_page_file(page),
+                &search_key(from, to, exclude_own_repos, repos),
+            )?
+            .unwrap_or(GitHubSearchPage {
+                total_count: 0,
+                incomplete_results: false,
+                items: Vec::new(),
+            }))
+    }
+
+    async fn search_opened_issues(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError> {
+        Ok(self
+            .load_keyed(
+                username,
+                &opened_issues_page_file(page),
+                &search_key(from, to, exclude_own_repos, repos),
+            )?
+            .unwrap_or(GitHubSearchPage {
+                total_count: 0,
+                incomplete_results: false,
+                items: Vec::new(),
+            }))
+    }
+
+    async fn fetch_repo_commits(
+        &self,
+        repo: &str,
+        username: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        page: u32,
+    ) -> Result<Vec<GitHubCommit>, GitHubError> {
+        Ok(self
+            .load_keyed(
+                username,
+                &repo_commits_page_file(repo, page),
+                &range_key(since, until),
+            )?
+            .unwrap_or_default())
+    }
+
+    async fn fetch_repo_languages(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<BTreeMap<String, u64>, GitHubError> {
+        let mut languages: BTreeMap<String, BTreeMap<String, u64>> =
+            self.load(username, LANGUAGES_FILE)?.unwrap_or_default();
+        Ok(languages
+            .remove(&repo.to_ascii_lowercase())
+            .unwrap_or_default())
+    }
+
+    async fn check_public_membership(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<bool, GitHubError> {
+        let memberships: BTreeMap<String, bool> = self
+            .load(username, ORG_MEMBERSHIPS_FILE)?
+            .unwrap_or_default();
+        Ok(memberships
+            .get(&org.to_ascii_lowercase())
+            .copied()
+            .unwrap_or(false))
+    }
+
+    async fn fetch_collaborator_permission(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<GitHubCollaboratorPermission, GitHubError> {
+        let mut permissions: BTreeMap<String, GitHubCollaboratorPermission> = self
+            .load(username, REPO_PERMISSIONS_FILE)?
+            .unwrap_or_default();
+        permissions
+            .remove(&repo.to_ascii_lowercase())
+            .ok_or_else(|| GitHubError::RepositoryNotFound(repo.to_string()))
+    }
+}
+
+/// The fixtures checked in under `tests/fixtures/github`.
+#[cfg(test)]
+pub fn test_fixtures() -> FixtureDataSource {
+    FixtureDataSource::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/github"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_load_fixture_user() {
+        let source = test_fixtures();
+
+        let user = source.fetch_user("Octocat").await.unwrap();
+        assert_eq!(user.login, "octocat");
+        assert_eq!(user.public_repos, 8);
+
+        let events = source
+            .fetch_user_events("octocat", &ProgressReporter::disabled())
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 3);
+
+        let repos = source.fetch_user_repos("octocat", 1).await.unwrap();
+        assert_eq!(repos.len(), 3);
+        // Pages past the recorded ones are empty, which ends pagination
+        assert!(source
+            .fetch_user_repos("octocat", 2)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_fixture_user() {
+        let result = test_fixtures().fetch_user("no-such-user").await;
+        assert!(matches!(result, Err(GitHubError::UserNotFound(_))));
+    }
+}
@@ -1,5 +1,15 @@
 pub mod client;
+#[cfg(any(test, feature = "dev-data-sources"))]
+pub mod fixture;
+#[cfg(any(test, feature = "dev-data-sources"))]
+pub mod recording;
+pub mod source;
 pub mod types;
 
 pub use client::GitHubClient;
+#[cfg(feature = "dev-data-sources")]
+pub use fixture::FixtureDataSource;
+#[cfg(feature = "dev-data-sources")]
+pub use recording::RecordingDataSource;
+pub use source::GitHubDataSource;
 pub use types::*;
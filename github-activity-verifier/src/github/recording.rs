@@ -0,0 +1,333 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::github::fixture::{
+    self, EVENTS_FILE, LANGUAGES_FILE, ORG_MEMBERSHIPS_FILE, REPO_PERMISSIONS_FILE,
+    REVIEW_CONTRIBUTIONS_FILE, USER_FILE,
+};
+use crate::github::{
+    GitHubCollaboratorPermission, GitHubCommit, GitHubDataSource, GitHubError, GitHubEvent,
+    GitHubIssue, GitHubReviewContributions, GitHubSearchPage, GitHubUser, GitHubUserRepo,
+};
+use crate::utils::progress::ProgressReporter;
+
+/// Passes every call through to `inner` and writes successful responses to
+/// `root` in the layout read by `FixtureDataSource`, so a live session can be
+/// replayed deterministically later. Responses to requests with a time range
+/// or search options are keyed by those parameters, so the same endpoint
+/// queried with different ranges keeps one recording per range.
+pub struct RecordingDataSource {
+    inner: Arc<dyn GitHubDataSource>,
+    root: PathBuf,
+}
+
+impl RecordingDataSource {
+    pub fn new(inner: Arc<dyn GitHubDataSource>, root: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            root: root.into(),
+        }
+    }
+
+    fn save<T: Serialize>(&self, username: &str, file_name: &str, value: &T) {
+        let dir = fixture::user_dir(&self.root, username);
+        let path = dir.join(file_name);
+
+        let result = serde_json::to_vec_pretty(value)
+            .map_err(std::io::Error::other)
+            .and_then(|content| {
+                std::fs::create_dir_all(&dir)?;
+                std::fs::write(&path, content)
+            });
+
+        match result {
+            Ok(()) => debug!("Recorded GitHub response to {}", path.display()),
+            // Recording is a side channel; never fail the request because of it
+            Err(err) => warn!("Failed to record {}: {}", path.display(), err),
+        }
+    }
+
+    /// Adds one entry to a recorded `{ key: value }` file, keeping the
+    /// entries recorded earlier.
+    fn save_entry<T: Serialize + DeserializeOwned>(
+        &self,
+        username: &str,
+        file_name: &str,
+        key: &str,
+        value: T,
+    ) {
+        let path = fixture::user_dir(&self.root, username).join(file_name);
+        let mut entries: BTreeMap<String, T> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        entries.insert(key.to_ascii_lowercase(), value);
+        self.save(username, file_name, &entries);
+    }
+}
+
+#[async_trait]
+impl GitHubDataSource for RecordingDataSource {
+    fn name(&self) -> &'static str {
+        "record"
+    }
+
+    async fn fetch_user_events(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<GitHubEvent>, GitHubError> {
+        let events = self.inner.fetch_user_events(username, progress).await?;
+        self.save(username, EVENTS_FILE, &events);
+        Ok(events)
+    }
+
+    async fn fetch_user(&self, username: &str) -> Result<GitHubUser, GitHubError> {
+        let user = self.inner.fetch_user(username).await?;
+        self.save(username, USER_FILE, &user);
+        Ok(user)
+    }
+
+    async fn fetch_user_repos(
+        &self,
+        username: &str,
+        page: u32,
+    ) -> Result<Vec<GitHubUserRepo>, GitHubError> {
+        let repos = self.inner.fetch_user_repos(username, page).await?;
+        self.save(username, &fixture::repos_page_file(page), &repos);
+        Ok(repos)
+    }
+
+    async fn fetch_review_contributions(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<GitHubReviewContributions, GitHubError> {
+        let reviews = self
+            .inner
+            .fetch_review_contributions(username, from, to)
+            .await?;
+        self.save(
+            username,
+            &fixture::keyed_file(REVIEW_CONTRIBUTIONS_FILE, &fixture::range_key(from, to)),
+            &reviews,
+        );
+        Ok(reviews)
+    }
+
+    async fn search_merged_pull_requests(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError> {
+        let results = self
+            .inner
+            .search_merged_pull_requests(username, from, to, exclude_own_repos, repos, page)
+            .await?;
+        let key = fixture::search_key(from, to, exclude_own_repos, repos);
+        self.save(
+            username,
+            &fixture::keyed_file(&fixture::merged_pulls_page_file(page), &key),
+            &results,
+        );
+        Ok(results)
+    }
+
+    async fn search_opened_issues(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError> {
+        let results = self
+            .inner
+            .search_opened_issues(username, from, to, exclude_own_repos, repos, page)
+            .await?;
+        let key = fixture::search_key(from, to, exclude_own_repos, repos);
+        self.save(
+            username,
+            &fixture::keyed_file(&fixture::opened_issues_page_file(page), &key),
+            &results,
+        );
+        Ok(results)
+    }
+
+    async fn fetch_repo_commits(
+        &self,
+        repo: &str,
+        username: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        page: u32,
+    ) -> Result<Vec<GitHubCommit>, GitHubError> {
+        let commits = self
+            .inner
+            .fetch_repo_commits(repo, username, since, until, page)
+            .await?;
+        self.save(
+            username,
+            &fixture::keyed_file(
+                &fixture::repo_commits_page_file(repo, page),
+                &fixture::range_key(since, until),
+            ),
+            &commits,
+        );
+        Ok(commits)
+    }
+
+    async fn fetch_repo_languages(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<BTreeMap<String, u64>, GitHubError> {
+        let languages = self.inner.fetch_repo_languages(repo, username).await?;
+        self.save_entry(username, LANGUAGES_FILE, repo, languages.clone());
+        Ok(languages)
+    }
+
+    async fn check_public_membership(
+        &self,
+        org: &str,
+        username: &str,
+    ) -> Result<bool, GitHubError> {
+        let is_member = self.inner.check_public_membership(org, username).await?;
+        self.save_entry(username, ORG_MEMBERSHIPS_FILE, org, is_member);
+        Ok(is_member)
+    }
+
+    async fn fetch_collaborator_permission(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<GitHubCollaboratorPermission, GitHubError> {
+        let permission = self
+            .inner
+            .fetch_collaborator_permission(repo, username)
+            .await?;
+        self.save_entry(username, REPO_PERMISSIONS_FILE, repo, permission.clone());
+        Ok(permission)
+    }
+
+    fn forget_user(&self, username: &str) -> std::io::Result<usize> {
+        let dir = fixture::user_dir(&self.root, username);
+        let files = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries.count(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+        std::fs::remove_dir_all(&dir)?;
+        debug!("Deleted {} recorded responses in {}", files, dir.display());
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::fixture::test_fixtures;
+    use crate::github::fixture::FixtureDataSource;
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let root = std::env::temp_dir().join(format!("gh-recording-{}", uuid::Uuid::new_v4()));
+        let recorder = RecordingDataSource::new(Arc::new(test_fixtures()), &root);
+        let progress = ProgressReporter::disabled();
+
+        let user = recorder.fetch_user("octocat").await.unwrap();
+        let events = recorder
+            .fetch_user_events("octocat", &progress)
+            .await
+            .unwrap();
+        let repos = recorder.fetch_user_repos("octocat", 1).await.unwrap();
+
+        let replay = FixtureDataSource::new(&root);
+        let replayed_user = replay.fetch_user("octocat").await.unwrap();
+        let replayed_events = replay
+            .fetch_user_events("octocat", &progress)
+            .await
+            .unwrap();
+        let replayed_repos = replay.fetch_user_repos("octocat", 1).await.unwrap();
+
+        assert_eq!(replayed_user.login, user.login);
+        assert_eq!(replayed_user.public_repos, user.public_repos);
+        assert_eq!(
+            replayed_events.iter().map(|e| &e.id).collect::<Vec<_>>(),
+            events.iter().map(|e| &e.id).collect::<Vec<_>>()
+        );
+        assert_eq!(replayed_repos.len(), repos.len());
+
+        assert_eq!(recorder.forget_user("Octocat").unwrap(), 3);
+        assert!(replay.fetch_user("octocat").await.is_err());
+        assert_eq!(recorder.forget_user("octocat").unwrap(), 0);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_ranges() {
+        let source_root =
+            std::env::temp_dir().join(format!("gh-ranges-source-{}", uuid::Uuid::new_v4()));
+        let root = std::env::temp_dir().join(format!("gh-ranges-{}", uuid::Uuid::new_v4()));
+        let repo = "octocat/Hello-World";
+        let epoch = DateTime::UNIX_EPOCH;
+        let (window_from, window_to) = (
+            "2026-01-01T00:00:00Z".parse().unwrap(),
+            "2026-06-01T00:00:00Z".parse().unwrap(),
+        );
+
+        // An upstream that answers each range differently
+        let dir = fixture::user_dir(&source_root, "octocat");
+        std::fs::create_dir_all(&dir).unwrap();
+        for (sha, since, until) in [("a1", epoch, window_to), ("b2", window_from, window_to)] {
+            let file = fixture::keyed_file(
+                &fixture::repo_commits_page_file(repo, 1),
+                &fixture::range_key(since, until),
+            );
+            let commits = serde_json::json!([{"sha": sha, "commit": {"author": null}}]);
+            std::fs::write(dir.join(file), commits.to_string()).unwrap();
+        }
+        let recorder =
+            RecordingDataSource::new(Arc::new(FixtureDataSource::new(&source_root)), &root);
+
+        let all = recorder
+            .fetch_repo_commits(repo, "octocat", epoch, window_to, 1)
+            .await
+            .unwrap();
+        let windowed = recorder
+            .fetch_repo_commits(repo, "octocat", window_from, window_to, 1)
+            .await
+            .unwrap();
+        assert_eq!(all[0].sha, "a1");
+        assert_eq!(windowed[0].sha, "b2");
+
+        // Both recordings replay, neither overwrote the other
+        let replay = FixtureDataSource::new(&root);
+        let replayed_all = replay
+            .fetch_repo_commits(repo, "octocat", epoch, window_to, 1)
+            .await
+            .unwrap();
+        let replayed_windowed = replay
+            .fetch_repo_commits(repo, "octocat", window_from, window_to, 1)
+            .await
+            .unwrap();
+        assert_eq!(replayed_all[0].sha, "a1");
+        assert_eq!(replayed_windowed[0].sha, "b2");
+
+        std::fs::remove_dir_all(&source_root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use crate::github::{
+    GitHubCollaboratorPermission, GitHubCommit, GitHubError, GitHubEvent, GitHubIssue,
+    GitHubReviewContributions, GitHubSearchPage, GitHubUser, GitHubUserRepo,
+};
+use crate::utils::progress::ProgressReporter;
+
+/// Everything the verification engine reads from GitHub.
+///
+/// `GitHubClient` is the live implementation; `FixtureDataSource` replays JSON
+/// files from disk and `RecordingDataSource` captures live responses into that
+/// same layout. The latter two are only built for tests and with the
+/// `dev-data-sources` feature.
+#[async_trait]
+pub trait GitHubDataSource: Send + Sync {
+    /// Short name of the source (`live`, `fixture` or `record`), as
+    /// configured through `GITHUB_DATA_SOURCE`.
+    fn name(&self) -> &'static str;
+
+    async fn fetch_user_events(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<GitHubEvent>, GitHubError>;
+
+    async fn fetch_user(&self, username: &str) -> Result<GitHubUser, GitHubError>;
+
+    async fn fetch_user_repos(
+        &self,
+        username: &str,
+        page: u32,
+    ) -> Result<Vec<GitHubUserRepo>, GitHubError>;
+
+    /// Pull request reviews submitted between `from` and `to`. Needs the
+    /// GraphQL API, so implementations without a token return
+    /// `GitHubError::TokenRequired`.
+    async fn fetch_review_contributions(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<GitHubReviewContributions, GitHubError>;
+
+    /// One page of the commits to `repo` (`owner/name`) authored by the user
+    /// between `since` and `until`.
+    async fn fetch_repo_commits(
+        &self,
+        repo: &str,
+        username: &str,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        page: u32,
+    ) -> Result<Vec<GitHubCommit>, GitHubError>;
+
+    /// Bytes of code per language in `repo` (`owner/name`).
+    async fn fetch_repo_languages(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<BTreeMap<String, u64>, GitHubError>;
+
+    /// Whether the user is a public member of `org`. Unknown organizations
+    /// are indistinguishable from non-membership.
+    async fn check_public_membership(&self, org: &str, username: &str)
+        -> Result<bool, GitHubError>;
+
+    /// The user's permission on `repo` (`owner/name`). GitHub only answers
+    /// this for tokens with push access to the repository.
+    async fn fetch_collaborator_permission(
+        &self,
+        repo: &str,
+        username: &str,
+    ) -> Result<GitHubCollaboratorPermission, GitHubError>;
+
+    /// One page of the user's pull requests merged between `from` and `to`,
+    /// optionally leaving out pull requests to repositories the user owns. A
+    /// non-empty `repos` (`owner/name`) limits the search to those
+    /// repositories.
+    async fn search_merged_pull_requests(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError>;
+
+    /// One page of the issues the user opened between `from` and `to`, with
+    /// the same repository filters as `search_merged_pull_requests`.
+    async fn search_opened_issues(
+        &self,
+        username: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_own_repos: bool,
+        repos: &[String],
+        page: u32,
+    ) -> Result<GitHubSearchPage<GitHubIssue>, GitHubError>;
+
+    /// Deletes every response kept for the user and returns how many were
+    /// deleted. Sources that keep nothing have nothing to delete.
+    fn forget_user(&self, _username: &str) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -7,6 +7,9 @@ pub enum GitHubError {
     #[error("User not found: {0}")]
     UserNotFound(String),
 
+    #[error("Repository not found: {0}")]
+    RepositoryNotFound(String),
+
     #[error("Rate limit exceeded")]
     RateLimit,
 
@@ -18,9 +21,28 @@ pub enum GitHubError {
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("GitHub token required: {0}")]
+    TokenRequired(String),
+
+    #[cfg(any(test, feature = "dev-data-sources"))]
+    #[error("Fixture error: {0}")]
+    Fixture(String),
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl GitHubError {
+    /// Failures on GitHub's side or on the way there, which say nothing about
+    /// the user being verified.
+    pub fn is_upstream(&self) -> bool {
+        match self {
+            GitHubError::RateLimit | GitHubError::Network(_) | GitHubError::Json(_) => true,
+            GitHubError::ApiError { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubEvent {
     pub id: String,
     #[serde(rename = "type")]
@@ -31,30 +53,245 @@ pub struct GitHubEvent {
     pub payload: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubActor {
     pub id: u64,
     pub login: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRepo {
     pub id: u64,
     pub name: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubUser {
     pub login: String,
     pub id: u64,
     pub public_repos: u32,
+    // Older recordings predate these fields
+    #[serde(default)]
+    pub followers: u32,
+    /// Public email address
+    #[serde(default)]
+    pub email: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// An entry of the repository commits endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommit {
+    pub sha: String,
+    pub commit: GitHubCommitDetail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommitDetail {
+    #[serde(default)]
+    pub author: Option<GitHubCommitSignature>,
+    #[serde(default)]
+    pub verification: Option<GitHubCommitVerification>,
+}
+
+/// Signature verification status of a commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommitVerification {
+    pub verified: bool,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommitSignature {
+    pub date: DateTime<Utc>,
+}
+
+impl GitHubCommit {
+    pub fn authored_at(&self) -> Option<DateTime<Utc>> {
+        self.commit.author.as_ref().map(|author| author.date)
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.commit
+            .verification
+            .as_ref()
+            .is_some_and(|verification| verification.verified)
+    }
+}
+
+/// Response of the collaborator permission endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCollaboratorPermission {
+    /// Legacy permission: `admin`, `write`, `read` or `none`
+    pub permission: String,
+    /// Fine-grained role: `admin`, `maintain`, `write`, `triage` or `read`
+    #[serde(default)]
+    pub role_name: String,
+}
+
+impl GitHubCollaboratorPermission {
+    /// The role as a rank, so that e.g. `maintain` satisfies a `push` requirement.
+    pub fn rank(&self) -> u8 {
+        let role = if self.role_name.is_empty() {
+            self.permission.as_str()
+        } else {
+            self.role_name.as_str()
+        };
+        match role {
+            "admin" => 4,
+            "maintain" => 3,
+            "write" | "push" => 2,
+            "triage" => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubUserRepo {
     pub id: u64,
     pub name: String,
     pub stargazers_count: u32,
     pub created_at: DateTime<Utc>,
+    // The fields below are missing from older recordings
+    #[serde(default)]
+    pub fork: bool,
+    /// Primary language as detected by GitHub
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub is_template: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubSearchPage<T> {
+    pub total_count: u32,
+    pub incomplete_results: bool,
+    pub items: Vec<T>,
+}
+
+/// An issue or pull request as returned by the issue search API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubIssue {
+    pub id: u64,
+    pub number: u64,
+    pub repository_url: String,
+    #[serde(default)]
+    pub pull_request: Option<GitHubIssuePullRequest>,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubIssuePullRequest {
+    pub merged_at: Option<DateTime<Utc>>,
+}
+
+impl GitHubIssue {
+    /// `owner/name` of the repository, taken from `repository_url`.
+    pub fn repository_full_name(&self) -> &str {
+        self.repository_url
+            .split("/repos/")
+            .nth(1)
+            .unwrap_or(&self.repository_url)
+    }
+
+    pub fn repository_owner(&self) -> &str {
+        self.repository_full_name()
+            .split('/')
+            .next()
+            .unwrap_or_default()
+    }
+
+    pub fn merged_at(&self) -> Option<DateTime<Utc>> {
+        self.pull_request.as_ref().and_then(|pr| pr.merged_at)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQLResponse<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<GraphQLError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQLError {
+    pub message: String,
+    #[serde(rename = "type", default)]
+    pub error_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQLUserQuery<T> {
+    pub user: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLConnection<T> {
+    pub page_info: GraphQLPageInfo,
+    pub nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLPageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewContributionsUser {
+    pub contributions_collection: ReviewContributionsCollection,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewContributionsCollection {
+    pub pull_request_review_contributions: GraphQLConnection<ReviewContributionNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewContributionNode {
+    pub occurred_at: DateTime<Utc>,
+    pub pull_request_review: ReviewNode,
+    pub repository: RepositoryNode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewNode {
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryNode {
+    pub name_with_owner: String,
+}
+
+/// The user's pull request reviews within a range. `truncated` is set when
+/// there were more reviews than the pages fetched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubReviewContributions {
+    pub reviews: Vec<GitHubReviewContribution>,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// A pull request review submitted by the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubReviewContribution {
+    pub occurred_at: DateTime<Utc>,
+    /// Review state as reported by GitHub, e.g. `APPROVED` or `commented`
+    pub state: String,
+    /// `owner/name` of the reviewed repository
+    pub repository: String,
 }
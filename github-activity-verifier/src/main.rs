@@ -1,8 +1,10 @@
 mod api;
 mod attestation;
+mod auth;
 mod github;
 mod utils;
 mod verification;
+mod webhook;
 
 use actix_files::Files;
 use actix_web::{middleware::Logger, web, App, HttpResponse, HttpServer, Result};
@@ -10,18 +12,40 @@ use std::sync::Arc;
 use tracing::{info, warn};
 
 use crate::api::handlers;
+use crate::utils::audit::{self, AuditEvent, AuditLog};
 use crate::utils::storage::ProofStorage;
+use crate::utils::throttle::RequestThrottle;
+use crate::utils::transparency::SignedTreeHead;
+use crate::verification::anomaly::AnomalyPolicy;
 
 pub type AppState = web::Data<Arc<AppData>>;
 
 pub struct AppData {
     pub proof_storage: ProofStorage,
-    pub github_client: github::GitHubClient,
+    pub github: Arc<dyn github::GitHubDataSource>,
     pub maa_client: attestation::MAAClient,
+    pub webhook_client: webhook::WebhookClient,
+    pub anomaly_policy: AnomalyPolicy,
+    pub verification_throttle: RequestThrottle,
+    pub auth: auth::Authenticator,
+    pub audit: AuditLog,
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("verify-audit-log") => return verify_audit_log(args.get(2).map(String::as_str)),
+        Some("verify-proof") => {
+            let Some(path) = args.get(2) else {
+                eprintln!("Usage: github-activity-verifier verify-proof <proof.json> [nonce]");
+                std::process::exit(2);
+            };
+            return verify_proof(path, args.get(3).map(String::as_str));
+        }
+        _ => {}
+    }
+
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
@@ -39,12 +63,67 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    // Check webhook configuration
+    let webhook_secret = std::env::var("WEBHOOK_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty());
+    let webhook_allowed_hosts: Vec<String> = std::env::var("WEBHOOK_ALLOWED_HOSTS")
+        .map(|hosts| hosts.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+    let webhook_client = webhook::WebhookClient::new(webhook_secret, webhook_allowed_hosts);
+    if webhook_client.is_enabled() {
+        info!(
+            "Webhook callbacks enabled for hosts: {:?}",
+            webhook_client.allowed_hosts()
+        );
+    } else {
+        info!(
+            "WEBHOOK_SECRET or WEBHOOK_ALLOWED_HOSTS not configured - webhook callbacks disabled"
+        );
+    }
+
+    // Check sign-in configuration
+    let oauth_client_id = std::env::var("GITHUB_OAUTH_CLIENT_ID")
+        .ok()
+        .filter(|id| !id.is_empty());
+    let oauth_client_secret = std::env::var("GITHUB_OAUTH_CLIENT_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty());
+    let oauth = auth::GitHubOAuth::new(oauth_client_id, oauth_client_secret);
+    if oauth.is_enabled() {
+        info!("Sign-in with GitHub enabled");
+    } else {
+        info!("GITHUB_OAUTH_CLIENT_ID or GITHUB_OAUTH_CLIENT_SECRET not configured - sign-in disabled");
+    }
+    let operator_key = std::env::var("OPERATOR_API_KEY")
+        .ok()
+        .filter(|key| !key.is_empty());
+    let operator_key_configured = operator_key.is_some();
+    if !operator_key_configured {
+        info!("OPERATOR_API_KEY not configured - operator actions disabled");
+    }
+
     // Initialize application state
     let app_data = Arc::new(AppData {
         proof_storage: ProofStorage::new(),
-        github_client: github::GitHubClient::new(),
+        github: github_data_source(!maa_endpoint.is_empty())?,
         maa_client: attestation::MAAClient::new(maa_endpoint),
+        webhook_client,
+        anomaly_policy: anomaly_policy(),
+        verification_throttle: verification_throttle(),
+        auth: auth::Authenticator::new(oauth, operator_key),
+        audit: audit_log()?,
     });
+    app_data.audit.record(AuditEvent::ConfigLoaded {
+        data_source: app_data.github.name(),
+        maa_configured: !app_data.maa_client.endpoint.is_empty(),
+        webhooks_enabled: app_data.webhook_client.is_enabled(),
+        sign_in_enabled: app_data.auth.oauth.is_enabled(),
+        operator_key_configured,
+        anomaly_policy: app_data.anomaly_policy,
+    });
+
+    spawn_log_attestation(app_data.clone());
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "9000".to_string());
     let bind_address = format!("0.0.0.0:{}", port);
@@ -55,8 +134,46 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(web::Data::new(app_data.clone()))
             .wrap(Logger::default())
-            .service(web::scope("/api").route("/verify", web::post().to(handlers::verify)))
+            .service(
+                web::scope("/api")
+                    .route("/verify", web::post().to(handlers::verify))
+                    .route("/verify/stream", web::post().to(handlers::verify_stream))
+                    .route(
+                        "/webhooks/deliveries/{proof_hash}",
+                        web::get().to(handlers::get_webhook_deliveries),
+                    )
+                    .route(
+                        "/proofs/{proof_hash}/revoke",
+                        web::post().to(handlers::revoke_proof),
+                    )
+                    .route("/users/{username}", web::delete().to(handlers::erase_user))
+                    .route(
+                        "/users/{username}/proofs",
+                        web::get().to(handlers::get_user_proofs),
+                    )
+                    .route("/status-list", web::get().to(handlers::get_status_list))
+                    .route("/log/root", web::get().to(handlers::get_log_root))
+                    .route(
+                        "/log/inclusion/{proof_hash}",
+                        web::get().to(handlers::get_log_inclusion),
+                    )
+                    .route(
+                        "/log/consistency",
+                        web::get().to(handlers::get_log_consistency),
+                    ),
+            )
+            .service(
+                web::scope("/auth")
+                    .route("/github/login", web::get().to(handlers::github_login))
+                    .route("/github/callback", web::get().to(handlers::github_callback))
+                    .route("/session", web::get().to(handlers::get_session))
+                    .route("/logout", web::post().to(handlers::logout)),
+            )
             .route("/proof/{proof_hash}", web::get().to(handlers::get_proof))
+            .route(
+                "/proof/{proof_hash}/histogram.svg",
+                web::get().to(handlers::get_proof_histogram_svg),
+            )
             .service(Files::new("/static", "./static").index_file("index.html"))
             .route("/", web::get().to(serve_index))
             .default_service(web::route().to(not_found))
@@ -66,6 +183,282 @@ async fn main() -> std::io::Result<()> {
     .await
 }
 
+/// Selects the GitHub data source from `GITHUB_DATA_SOURCE`: `live`
+/// (default), or with the `dev-data-sources` feature `fixture` (replay from
+/// `GITHUB_FIXTURE_DIR`) or `record` (live, saving every response to
+/// `GITHUB_FIXTURE_DIR`).
+fn github_data_source(maa_configured: bool) -> std::io::Result<Arc<dyn github::GitHubDataSource>> {
+    let mode = std::env::var("GITHUB_DATA_SOURCE").unwrap_or_else(|_| "live".to_string());
+
+    match mode.as_str() {
+        "live" => Ok(Arc::new(github::GitHubClient::new())),
+        "fixture" | "record" => dev_data_source(&mode, maa_configured),
+        other => {
+            warn!(
+                "Unknown GITHUB_DATA_SOURCE {:?} - using live GitHub data",
+                other
+            );
+            Ok(Arc::new(github::GitHubClient::new()))
+        }
+    }
+}
+
+/// Proofs don't say where their data came from, so replayed data must never
+/// be attested: the fixture source refuses to run with MAA configured.
+#[cfg(feature = "dev-data-sources")]
+fn dev_data_source(
+    mode: &str,
+    maa_configured: bool,
+) -> std::io::Result<Arc<dyn github::GitHubDataSource>> {
+    let fixture_dir =
+        std::env::var("GITHUB_FIXTURE_DIR").unwrap_or_else(|_| "./fixtures/github".to_string());
+
+    if mode == "fixture" {
+        if maa_configured {
+            return Err(std::io::Error::other(
+                "GITHUB_DATA_SOURCE=fixture can't be combined with MAA_ENDPOINT: replayed data must not be attested",
+            ));
+        }
+        warn!("Replaying GitHub data from fixtures in {}", fixture_dir);
+        Ok(Arc::new(github::FixtureDataSource::new(fixture_dir)))
+    } else {
+        warn!("Recording GitHub responses to {}", fixture_dir);
+        Ok(Arc::new(github::RecordingDataSource::new(
+            Arc::new(github::GitHubClient::new()),
+            fixture_dir,
+        )))
+    }
+}
+
+#[cfg(not(feature = "dev-data-sources"))]
+fn dev_data_source(
+    mode: &str,
+    _maa_configured: bool,
+) -> std::io::Result<Arc<dyn github::GitHubDataSource>> {
+    Err(std::io::Error::other(format!(
+        "GITHUB_DATA_SOURCE={} needs a build with the dev-data-sources feature",
+        mode
+    )))
+}
+
+/// Every `LOG_ATTESTATION_INTERVAL_SECS` seconds (default 300), attests the
+/// transparency log head and the audit log head through MAA if they changed,
+/// so that third parties can hold the enclave to the logs it presented.
+fn spawn_log_attestation(app_data: Arc<AppData>) {
+    if app_data.maa_client.endpoint.is_empty() {
+        warn!("MAA not configured - transparency and audit log heads won't be attested");
+        return;
+    }
+    let interval_secs = std::env::var("LOG_ATTESTATION_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(300);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        let mut attested_audit_seq = None;
+        loop {
+            interval.tick().await;
+            attest_transparency_log(&app_data).await;
+            attest_audit_log(&app_data, &mut attested_audit_seq).await;
+        }
+    });
+}
+
+async fn attest_transparency_log(app_data: &AppData) {
+    let log = app_data.proof_storage.log();
+    let head = log.head();
+    let attested_size = log.signed_head().map(|signed| signed.head.tree_size);
+    if head.tree_size == 0 || attested_size == Some(head.tree_size) {
+        return;
+    }
+
+    match app_data
+        .maa_client
+        .get_attestation_token(&head.attestation_data(), None, None)
+        .await
+    {
+        Ok(token) => {
+            info!("Attested transparency log head at size {}", head.tree_size);
+            log.record_signed_head(SignedTreeHead {
+                head,
+                timestamp: chrono::Utc::now(),
+                attestation_token: token,
+            });
+        }
+        Err(err) => {
+            warn!("Failed to attest transparency log head: {}", err);
+            app_data.audit.record(AuditEvent::MaaFailure {
+                purpose: "transparency_log_head",
+                error: err.to_string(),
+            });
+        }
+    }
+}
+
+/// Records an attestation of the latest audit record in the audit log
+/// itself. `attested_seq` is the attestation record last written, so an
+/// otherwise idle log isn't attested over and over.
+async fn attest_audit_log(app_data: &AppData, attested_seq: &mut Option<u64>) {
+    let Some((seq, hash)) = app_data.audit.head() else {
+        return;
+    };
+    if *attested_seq == Some(seq) {
+        return;
+    }
+
+    let runtime_data = format!("audit:{}:{}", seq, hash);
+    match app_data
+        .maa_client
+        .get_attestation_token(&runtime_data, None, None)
+        .await
+    {
+        Ok(token) => {
+            info!("Attested audit log head at record {}", seq);
+            app_data.audit.record(AuditEvent::HeadAttested {
+                seq,
+                hash,
+                attestation_token: token,
+            });
+            *attested_seq = app_data.audit.head().map(|(seq, _)| seq);
+        }
+        Err(err) => {
+            warn!("Failed to attest audit log head: {}", err);
+            app_data.audit.record(AuditEvent::MaaFailure {
+                purpose: "audit_log_head",
+                error: err.to_string(),
+            });
+        }
+    }
+}
+
+/// Opens the audit log sink selected by `AUDIT_LOG_SINK`: `stdout` (default,
+/// JSON lines) or `file` (appended to `AUDIT_LOG_FILE`, default
+/// `./audit.log`, continuing the chain already there). Users are recorded
+/// under pseudonyms keyed with `AUDIT_PSEUDONYM_KEY`.
+fn audit_log() -> std::io::Result<AuditLog> {
+    let sink = std::env::var("AUDIT_LOG_SINK").unwrap_or_else(|_| "stdout".to_string());
+    let log = match sink.as_str() {
+        "file" => {
+            let path =
+                std::env::var("AUDIT_LOG_FILE").unwrap_or_else(|_| "./audit.log".to_string());
+            info!("Writing the audit log to {}", path);
+            AuditLog::file(std::path::Path::new(&path)).map_err(std::io::Error::other)?
+        }
+        "stdout" => AuditLog::stdout(),
+        other => {
+            warn!(
+                "Unknown AUDIT_LOG_SINK {:?} - writing the audit log to stdout",
+                other
+            );
+            AuditLog::stdout()
+        }
+    };
+
+    match std::env::var("AUDIT_PSEUDONYM_KEY") {
+        Ok(key) if !key.is_empty() => Ok(log.with_pseudonym_key(key.as_bytes())),
+        _ => {
+            warn!(
+                "AUDIT_PSEUDONYM_KEY not configured - audit pseudonyms won't match across restarts"
+            );
+            Ok(log)
+        }
+    }
+}
+
+/// `verify-audit-log [path]`: checks the hash chain of an audit log file, or
+/// of stdin when no path is given, and exits non-zero if it is broken.
+fn verify_audit_log(path: Option<&str>) -> std::io::Result<()> {
+    let result = match path {
+        Some(path) => audit::verify_chain(std::io::BufReader::new(std::fs::File::open(path)?)),
+        None => audit::verify_chain(std::io::stdin().lock()),
+    };
+
+    match result {
+        Ok(summary) => {
+            println!(
+                "Audit log intact: {} records in {} chains, head {}",
+                summary.records,
+                summary.chains,
+                summary.head_hash.as_deref().unwrap_or("-")
+            );
+            Ok(())
+        }
+        Err(err) => {
+            eprintln!("Audit log verification failed: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `verify-proof <proof.json> [nonce]`: checks offline that a proof returned
+/// by `/api/verify` matches its hash and is attested for it and, if given,
+/// the verifier's nonce, and exits non-zero if not.
+fn verify_proof(path: &str, nonce: Option<&str>) -> std::io::Result<()> {
+    let proof: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?)?;
+    match attestation::offline::check_proof(&proof, nonce) {
+        Ok(()) => {
+            println!(
+                "Proof {} is attested{}",
+                proof["proof_hash"].as_str().unwrap_or_default(),
+                if nonce.is_some() {
+                    " for this nonce"
+                } else {
+                    ""
+                }
+            );
+            Ok(())
+        }
+        Err(err) => {
+            eprintln!("Proof verification failed: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads `ANOMALY_POLICY` (`exclude`, `downgrade`, `off` or `report`, the default),
+/// which decides how activity flagged as possible gaming is treated.
+fn anomaly_policy() -> AnomalyPolicy {
+    let policy = match std::env::var("ANOMALY_POLICY") {
+        Ok(value) if !value.is_empty() => value.parse().unwrap_or_else(|err| {
+            warn!("{} - falling back to reporting anomalies", err);
+            AnomalyPolicy::default()
+        }),
+        _ => AnomalyPolicy::default(),
+    };
+    info!("Anomaly policy: {:?}", policy);
+    policy
+}
+
+/// Per-user, per-criterion request limit: `VERIFY_THROTTLE_LIMIT` requests
+/// (default 10) every `VERIFY_THROTTLE_WINDOW_SECS` seconds (default 3600).
+fn verification_throttle() -> RequestThrottle {
+    // A limit of 0 would turn away every verification
+    let limit = match std::env::var("VERIFY_THROTTLE_LIMIT") {
+        Ok(value) if !value.is_empty() => match value.parse::<usize>() {
+            Ok(limit) if limit > 0 => limit,
+            _ => {
+                warn!(
+                    "Invalid VERIFY_THROTTLE_LIMIT '{}' - must be a positive number, falling back to 10",
+                    value
+                );
+                10
+            }
+        },
+        _ => 10,
+    };
+    let window_secs = std::env::var("VERIFY_THROTTLE_WINDOW_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(3600);
+    info!(
+        "Verification throttle: {} requests per user and criterion every {} seconds",
+        limit, window_secs
+    );
+    RequestThrottle::new(limit, chrono::Duration::seconds(window_secs))
+}
+
 async fn serve_index() -> Result<HttpResponse> {
     let index_content = std::fs::read_to_string("./static/index.html").unwrap_or_else(|_| {
         r#"<!DOCTYPE html>
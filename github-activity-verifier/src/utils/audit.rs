@@ -0,0 +1,378 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::api::types::{Revoker, VerificationType};
+use crate::verification::anomaly::AnomalyPolicy;
+
+/// `prev_hash` of the first record of a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Record {seq} on line {line} is broken: {reason}")]
+    BrokenChain {
+        line: usize,
+        seq: u64,
+        reason: String,
+    },
+}
+
+/// Administrative and verification events worth keeping beyond the
+/// `tracing` output. Records can't be removed without breaking the chain, so
+/// users are only named by `AuditLog::pseudonym`, never by their login.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// The server started with this configuration (secrets are only
+    /// recorded as being set or not)
+    ConfigLoaded {
+        data_source: &'static str,
+        maa_configured: bool,
+        webhooks_enabled: bool,
+        sign_in_enabled: bool,
+        operator_key_configured: bool,
+        anomaly_policy: AnomalyPolicy,
+    },
+    VerificationRequested {
+        subject: String,
+        verification_type: VerificationType,
+    },
+    DataSourceUsed {
+        subject: String,
+        data_source: &'static str,
+    },
+    ProofIssued {
+        subject: String,
+        verification_type: VerificationType,
+        proof_hash: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        log_index: Option<u64>,
+    },
+    /// An MAA attestation failed; `purpose` is what was being attested
+    MaaFailure {
+        purpose: &'static str,
+        error: String,
+    },
+    ProofRevoked {
+        proof_hash: String,
+        revoked_by: Revoker,
+    },
+    /// Tombstone of a right-to-erasure request. Names the user's pseudonym
+    /// but none of the deleted data.
+    UserErased {
+        subject: String,
+        proofs_deleted: usize,
+        receipt_hash: String,
+    },
+    /// An MAA token over `audit:<seq>:<hash>` of an earlier record, which
+    /// anchors the chain up to that record
+    HeadAttested {
+        seq: u64,
+        hash: String,
+        attestation_token: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct AuditRecord<'a> {
+    seq: u64,
+    timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a AuditEvent,
+    prev_hash: &'a str,
+}
+
+struct ChainState {
+    next_seq: u64,
+    prev_hash: String,
+    sink: Box<dyn Write + Send>,
+}
+
+/// Hash-chained audit log written as JSON lines. Each record carries the
+/// `hash` of the one before it as `prev_hash`, and its own `hash` is the
+/// SHA-256 of the record without `hash`, serialized with sorted keys, so
+/// altering, removing or reordering records breaks the chain.
+pub struct AuditLog {
+    state: Mutex<ChainState>,
+    pseudonym_key: Vec<u8>,
+}
+
+impl AuditLog {
+    /// Starts with a random pseudonym key, so pseudonyms only link records
+    /// written by this process unless a key is set.
+    fn new(sink: Box<dyn Write + Send>, next_seq: u64, prev_hash: String) -> Self {
+        let pseudonym_key = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        Self {
+            state: Mutex::new(ChainState {
+                next_seq,
+                prev_hash,
+                sink,
+            }),
+            pseudonym_key: pseudonym_key.into_bytes(),
+        }
+    }
+
+    /// Sets the server secret that pseudonyms are keyed with.
+    pub fn with_pseudonym_key(mut self, key: &[u8]) -> Self {
+        self.pseudonym_key = key.to_vec();
+        self
+    }
+
+    /// The name a user is recorded under: HMAC-SHA256 of the lowercase login
+    /// under the pseudonym key, in hex. Logins are public and easy to
+    /// enumerate, so the key must stay secret for pseudonyms to protect them.
+    pub fn pseudonym(&self, username: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.pseudonym_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(username.to_ascii_lowercase().as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+
+    /// Writes records to stdout, starting a new chain.
+    pub fn stdout() -> Self {
+        Self::new(Box::new(std::io::stdout()), 0, GENESIS_HASH.to_string())
+    }
+
+    /// Appends records to `path`, continuing the chain already in the file.
+    pub fn file(path: &Path) -> Result<Self, AuditError> {
+        let (next_seq, prev_hash) = match std::fs::read_to_string(path) {
+            Ok(content) => content
+                .lines()
+                .rev()
+                .find_map(parse_record)
+                .and_then(|record| Some((record["seq"].as_u64()? + 1, record_hash(&record)?)))
+                .unwrap_or((0, GENESIS_HASH.to_string())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => (0, GENESIS_HASH.to_string()),
+            Err(err) => return Err(err.into()),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(Box::new(file), next_seq, prev_hash))
+    }
+
+    /// Appends an event. A failing sink is logged but never fails the
+    /// request that caused the event.
+    pub fn record(&self, event: AuditEvent) {
+        let mut state = self.state.lock().unwrap();
+        let record = AuditRecord {
+            seq: state.next_seq,
+            timestamp: Utc::now(),
+            event: &event,
+            prev_hash: &state.prev_hash,
+        };
+        let mut value = serde_json::to_value(&record).expect("Audit records serialize");
+        let hash = hash_value(&value);
+        value["hash"] = Value::String(hash.clone());
+
+        let line = format!("{}\n", value);
+        let result = state
+            .sink
+            .write_all(line.as_bytes())
+            .and_then(|_| state.sink.flush());
+        if let Err(err) = result {
+            warn!("Failed to write audit record {}: {}", state.next_seq, err);
+        }
+        state.next_seq += 1;
+        state.prev_hash = hash;
+    }
+
+    /// Sequence number and hash of the latest record.
+    pub fn head(&self) -> Option<(u64, String)> {
+        let state = self.state.lock().unwrap();
+        state
+            .next_seq
+            .checked_sub(1)
+            .map(|seq| (seq, state.prev_hash.clone()))
+    }
+}
+
+/// Result of a successful chain check.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChainSummary {
+    pub records: u64,
+    /// Chains started from the genesis hash, one per stdout-sink restart
+    pub chains: u64,
+    pub head_hash: Option<String>,
+}
+
+/// Checks every audit record read from `reader`. Lines that aren't audit
+/// records (e.g. `tracing` output sharing stdout) are skipped; records that
+/// were altered, removed or reordered are reported.
+pub fn verify_chain(reader: impl BufRead) -> Result<ChainSummary, AuditError> {
+    let mut summary = ChainSummary {
+        records: 0,
+        chains: 0,
+        head_hash: None,
+    };
+    let mut expected_seq = 0;
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let Some(record) = parse_record(&line) else {
+            continue;
+        };
+        let seq = record["seq"].as_u64().unwrap_or_default();
+        let broken = |reason: &str| AuditError::BrokenChain {
+            line: number + 1,
+            seq,
+            reason: reason.to_string(),
+        };
+
+        let prev_hash = record["prev_hash"].as_str().unwrap_or_default();
+        if seq == 0 && prev_hash == GENESIS_HASH {
+            summary.chains += 1;
+        } else if summary.head_hash.is_none() {
+            return Err(broken("the log doesn't start at a genesis record"));
+        } else if seq != expected_seq {
+            return Err(broken(&format!("expected record {}", expected_seq)));
+        } else if summary.head_hash.as_deref() != Some(prev_hash) {
+            return Err(broken("prev_hash doesn't match the previous record"));
+        }
+
+        let Some(hash) = record_hash(&record) else {
+            return Err(broken("hash doesn't match the record"));
+        };
+        summary.records += 1;
+        summary.head_hash = Some(hash);
+        expected_seq = seq + 1;
+    }
+
+    Ok(summary)
+}
+
+/// Parses a line that looks like an audit record.
+fn parse_record(line: &str) -> Option<Value> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    (value["seq"].is_u64() && value["prev_hash"].is_string() && value["hash"].is_string())
+        .then_some(value)
+}
+
+/// The record's `hash`, if it matches the rest of the record.
+fn record_hash(record: &Value) -> Option<String> {
+    let mut unhashed = record.clone();
+    let hash = unhashed.as_object_mut()?.remove("hash")?;
+    let hash = hash.as_str()?.to_string();
+    (hash == hash_value(&unhashed)).then_some(hash)
+}
+
+fn hash_value(value: &Value) -> String {
+    // serde_json maps keep their keys sorted, which makes this canonical
+    format!("{:x}", Sha256::digest(value.to_string().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn write_chain(records: u64) -> Vec<String> {
+        let buffer = SharedBuffer::default();
+        let log = AuditLog::new(Box::new(buffer.clone()), 0, GENESIS_HASH.to_string());
+        for i in 0..records {
+            log.record(AuditEvent::VerificationRequested {
+                subject: format!("user{}", i),
+                verification_type: VerificationType::TotalStars,
+            });
+        }
+        assert_eq!(log.head().map(|(seq, _)| seq), records.checked_sub(1));
+
+        let content = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        content.lines().map(str::to_string).collect()
+    }
+
+    fn verify(lines: &[String]) -> Result<ChainSummary, AuditError> {
+        verify_chain(lines.join("\n").as_bytes())
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let lines = write_chain(4);
+        let summary = verify(&lines).unwrap();
+        assert_eq!(summary.records, 4);
+        assert_eq!(summary.chains, 1);
+
+        // Interleaved log output and a restarted chain are accepted
+        let mut mixed = lines.clone();
+        mixed.insert(
+            2,
+            "2025-06-01T12:00:00Z INFO Verification completed".to_string(),
+        );
+        mixed.extend(write_chain(2));
+        let summary = verify(&mixed).unwrap();
+        assert_eq!((summary.records, summary.chains), (6, 2));
+
+        let tampered: Vec<String> = lines
+            .iter()
+            .map(|line| line.replace("user2", "user9"))
+            .collect();
+        assert!(matches!(
+            verify(&tampered),
+            Err(AuditError::BrokenChain { seq: 2, .. })
+        ));
+
+        let mut removed = lines.clone();
+        removed.remove(1);
+        assert!(matches!(
+            verify(&removed),
+            Err(AuditError::BrokenChain { seq: 2, .. })
+        ));
+        assert!(verify(&lines[1..]).is_err());
+    }
+
+    #[test]
+    fn test_pseudonyms() {
+        let log = AuditLog::stdout().with_pseudonym_key(b"server secret");
+        let pseudonym = log.pseudonym("Octocat");
+        assert_eq!(pseudonym, log.pseudonym("octocat"));
+        assert_eq!(pseudonym.len(), 64);
+        assert_ne!(pseudonym, log.pseudonym("hubot"));
+
+        let other_key = AuditLog::stdout().with_pseudonym_key(b"another secret");
+        assert_ne!(pseudonym, other_key.pseudonym("octocat"));
+    }
+
+    #[test]
+    fn test_file_sink_continues_chain() {
+        let path = std::env::temp_dir().join(format!("audit-{}.log", uuid::Uuid::new_v4()));
+        for _ in 0..2 {
+            let log = AuditLog::file(&path).unwrap();
+            log.record(AuditEvent::MaaFailure {
+                purpose: "proof",
+                error: "timeout".to_string(),
+            });
+        }
+
+        let file = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+        let summary = verify_chain(file).unwrap();
+        assert_eq!((summary.records, summary.chains), (2, 1));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
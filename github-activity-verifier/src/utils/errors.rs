@@ -17,19 +17,36 @@ pub enum AppError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Too many requests: {0}")]
+    Throttled(String),
+
+    #[error("OAuth error: {0}")]
+    OAuth(#[from] crate::auth::OAuthError),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
-impl From<AppError> for HttpResponse {
-    fn from(error: AppError) -> Self {
-        let (status, error_code, message) = match error {
-            AppError::GitHub(ref gh_err) => match gh_err {
+impl AppError {
+    pub fn to_api_error(&self) -> (StatusCode, ApiError) {
+        let (status, error_code, message) = match self {
+            AppError::GitHub(gh_err) => match gh_err {
                 crate::github::GitHubError::UserNotFound(username) => (
                     StatusCode::NOT_FOUND,
                     "USER_NOT_FOUND",
                     format!("GitHub user '{}' not found", username),
                 ),
+                crate::github::GitHubError::RepositoryNotFound(repo) => (
+                    StatusCode::NOT_FOUND,
+                    "REPOSITORY_NOT_FOUND",
+                    format!("GitHub repository '{}' not found", repo),
+                ),
                 crate::github::GitHubError::RateLimit => (
                     StatusCode::TOO_MANY_REQUESTS,
                     "RATE_LIMIT_EXCEEDED",
@@ -50,9 +67,44 @@ impl From<AppError> for HttpResponse {
                     "JSON_PARSE_ERROR",
                     "Failed to parse GitHub API response".to_string(),
                 ),
+                crate::github::GitHubError::TokenRequired(feature) => (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "GITHUB_TOKEN_REQUIRED",
+                    format!("{} requires a configured GitHub token", feature),
+                ),
+                #[cfg(any(test, feature = "dev-data-sources"))]
+                crate::github::GitHubError::Fixture(_) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "FIXTURE_ERROR",
+                    "Failed to load recorded GitHub data".to_string(),
+                ),
             },
-            AppError::Validation(msg) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg),
+            AppError::Validation(msg) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg.clone()),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg.clone()),
+            AppError::OAuth(oauth_err) => match oauth_err {
+                crate::auth::OAuthError::NotConfigured => (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "OAUTH_NOT_CONFIGURED",
+                    "Sign-in with GitHub is not configured on this server".to_string(),
+                ),
+                crate::auth::OAuthError::Network(_) => (
+                    StatusCode::BAD_GATEWAY,
+                    "NETWORK_ERROR",
+                    "Failed to connect to GitHub".to_string(),
+                ),
+                _ => (
+                    StatusCode::UNAUTHORIZED,
+                    "OAUTH_FAILED",
+                    oauth_err.to_string(),
+                ),
+            },
+            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg.clone()),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, "FORBIDDEN", msg.clone()),
+            AppError::Throttled(msg) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "VERIFICATION_THROTTLED",
+                msg.clone(),
+            ),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "INTERNAL_ERROR",
@@ -60,10 +112,20 @@ impl From<AppError> for HttpResponse {
             ),
         };
 
-        HttpResponse::build(status).json(ApiError {
-            error: message,
-            error_code: error_code.to_string(),
-            details: None,
-        })
+        (
+            status,
+            ApiError {
+                error: message,
+                error_code: error_code.to_string(),
+                details: None,
+            },
+        )
+    }
+}
+
+impl From<AppError> for HttpResponse {
+    fn from(error: AppError) -> Self {
+        let (status, api_error) = error.to_api_error();
+        HttpResponse::build(status).json(api_error)
     }
 }
@@ -1,3 +1,8 @@
+pub mod audit;
 pub mod errors;
+pub mod progress;
+pub mod status_list;
 pub mod storage;
+pub mod throttle;
+pub mod transparency;
 pub mod validation;
@@ -0,0 +1,75 @@
+use serde::Serialize;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationPhase {
+    FetchingEvents,
+    FetchingRepos,
+    FetchingUser,
+    FetchingPullRequests,
+    FetchingIssues,
+    FetchingReviews,
+    FetchingCommits,
+    FetchingLanguages,
+    CheckingAccess,
+    Evaluating,
+    Attesting,
+    Storing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressEvent {
+    pub phase: VerificationPhase,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+/// Reports verification progress to a listener (e.g. the SSE endpoint).
+/// A disabled reporter silently drops every event.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    sender: Option<UnboundedSender<ProgressEvent>>,
+    redact_counts: bool,
+}
+
+impl ProgressReporter {
+    pub fn disabled() -> Self {
+        Self {
+            sender: None,
+            redact_counts: false,
+        }
+    }
+
+    pub fn channel() -> (Self, UnboundedReceiver<ProgressEvent>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (
+            Self {
+                sender: Some(sender),
+                redact_counts: false,
+            },
+            receiver,
+        )
+    }
+
+    /// A reporter to the same listener that leaves out counts, which could
+    /// reveal the measured value.
+    pub fn without_counts(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            redact_counts: true,
+        }
+    }
+
+    pub fn report(&self, phase: VerificationPhase, message: impl Into<String>, count: Option<u32>) {
+        if let Some(sender) = &self.sender {
+            // The listener may have disconnected; progress is best-effort
+            let _ = sender.send(ProgressEvent {
+                phase,
+                message: message.into(),
+                count: count.filter(|_| !self.redact_counts),
+            });
+        }
+    }
+}
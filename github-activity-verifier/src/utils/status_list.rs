@@ -0,0 +1,101 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+pub const STATUS_LIST_PATH: &str = "/api/status-list";
+// The Bitstring Status List spec asks for at least 16KB so that a list
+// doesn't reveal how many proofs were issued
+const MIN_LIST_BITS: usize = 131_072;
+
+/// A proof's revocation bit. It is allocated before the proof is hashed and
+/// bound into both the proof hash and the attestation runtime data, so a
+/// revoked proof can't be passed off under another, unset bit.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct StatusListEntry {
+    pub status_list_index: u32,
+    pub status_list_credential: &'static str,
+}
+
+impl StatusListEntry {
+    pub fn new(status_list_index: u32) -> Self {
+        Self {
+            status_list_index,
+            status_list_credential: STATUS_LIST_PATH,
+        }
+    }
+}
+
+/// GZIP-compressed bitstring with the bits at `revoked` set, as a multibase
+/// base64url string. Bit 0 is the most significant bit of the first byte.
+pub fn encode_bitstring(revoked: &BTreeSet<u32>, allocated: u32) -> String {
+    let bits = (allocated as usize).max(MIN_LIST_BITS).div_ceil(8) * 8;
+    let mut bitstring = vec![0u8; bits / 8];
+    for &index in revoked {
+        bitstring[index as usize / 8] |= 0x80 >> (index % 8);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&bitstring)
+        .expect("Writing to memory can't fail");
+    let compressed = encoder.finish().expect("Writing to memory can't fail");
+    format!("u{}", general_purpose::URL_SAFE_NO_PAD.encode(compressed))
+}
+
+/// A Bitstring Status List document for revocation, published by `base_url`.
+pub fn status_list_document(
+    base_url: &str,
+    encoded_list: String,
+    valid_from: DateTime<Utc>,
+) -> serde_json::Value {
+    let id = format!("{}{}", base_url, STATUS_LIST_PATH);
+    json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2"],
+        "id": id,
+        "type": ["VerifiableCredential", "BitstringStatusListCredential"],
+        "issuer": base_url,
+        "validFrom": valid_from,
+        "credentialSubject": {
+            "id": format!("{}#list", id),
+            "type": "BitstringStatusList",
+            "statusPurpose": "revocation",
+            "encodedList": encoded_list,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn decode(encoded: &str) -> Vec<u8> {
+        let compressed = general_purpose::URL_SAFE_NO_PAD
+            .decode(encoded.strip_prefix('u').unwrap())
+            .unwrap();
+        let mut bitstring = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut bitstring)
+            .unwrap();
+        bitstring
+    }
+
+    #[test]
+    fn test_encode_bitstring() {
+        let revoked: BTreeSet<u32> = [0, 9, 15].into_iter().collect();
+        let bitstring = decode(&encode_bitstring(&revoked, 16));
+        assert_eq!(bitstring.len(), MIN_LIST_BITS / 8);
+        assert_eq!(bitstring[0], 0b1000_0000);
+        assert_eq!(bitstring[1], 0b0100_0001);
+        assert!(bitstring[2..].iter().all(|&byte| byte == 0));
+
+        // Lists grow past the minimum size with the proofs issued
+        let bitstring = decode(&encode_bitstring(&BTreeSet::new(), 200_001));
+        assert_eq!(bitstring.len(), 25_001);
+    }
+}
@@ -1,13 +1,37 @@
 use chrono::{DateTime, Duration, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info};
 
-use crate::api::types::VerificationResult;
+use crate::api::types::{ProofState, ProofStatus, Revoker, VerificationResult, VerificationType};
+use crate::utils::status_list::{self, StatusListEntry};
+use crate::utils::transparency::TransparencyLog;
 
 #[derive(Debug, Clone)]
 pub struct ProofStorage {
     proofs: Arc<RwLock<HashMap<String, StoredProof>>>,
+    status_list: Arc<RwLock<StatusList>>,
+    log: TransparencyLog,
+    user_index: Arc<RwLock<UserIndex>>,
+}
+
+/// Proof hashes by lowercase username and criterion, in issue order.
+type UserIndex = HashMap<String, HashMap<VerificationType, Vec<String>>>;
+
+/// Status list bits outlive the proofs they belong to, so that a revoked
+/// proof stays revoked for offline verifiers after it expires here.
+#[derive(Debug, Default)]
+struct StatusList {
+    allocated: u32,
+    revoked: BTreeSet<u32>,
+}
+
+/// What `erase_user` deleted: the proof hashes, sorted, and the status list
+/// bits it set for them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ErasedProofs {
+    pub proof_hashes: Vec<String>,
+    pub revoked_status_indexes: Vec<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,13 +45,45 @@ impl ProofStorage {
     pub fn new() -> Self {
         Self {
             proofs: Arc::new(RwLock::new(HashMap::new())),
+            status_list: Arc::new(RwLock::new(StatusList::default())),
+            log: TransparencyLog::new(),
+            user_index: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    pub async fn store_proof(&self, proof_hash: String, result: VerificationResult) {
+    /// The append-only log of every proof hash stored.
+    pub fn log(&self) -> &TransparencyLog {
+        &self.log
+    }
+
+    /// Reserves the next status list bit for a proof about to be issued.
+    pub fn allocate_status_entry(&self) -> StatusListEntry {
+        let mut status_list = self.status_list.write().unwrap();
+        status_list.allocated += 1;
+        StatusListEntry::new(status_list.allocated - 1)
+    }
+
+    /// Stores the proof with an active status at its allocated status list
+    /// bit, appends its hash to the transparency log and returns it as stored.
+    pub async fn store_proof(
+        &self,
+        proof_hash: String,
+        status_entry: StatusListEntry,
+        mut result: VerificationResult,
+    ) -> VerificationResult {
+        result.log_index = Some(self.log.append(&proof_hash));
+        result.status = Some(ProofStatus {
+            state: ProofState::Active,
+            status_list_index: status_entry.status_list_index,
+            status_list_credential: status_entry.status_list_credential,
+            revoked_at: None,
+            revoked_by: None,
+            reason: None,
+        });
+
         let expires_at = Utc::now() + Duration::hours(24);
         let stored_proof = StoredProof {
-            verification_result: result,
+            verification_result: result.clone(),
             created_at: Utc::now(),
             expires_at,
         };
@@ -39,11 +95,134 @@ impl ProofStorage {
             // Cleanup expired proofs while we have the write lock
             self.cleanup_expired(&mut proofs);
         }
+        self.user_index
+            .write()
+            .unwrap()
+            .entry(result.username.to_ascii_lowercase())
+            .or_default()
+            .entry(result.verification_type)
+            .or_default()
+            .push(proof_hash.clone());
 
         info!(
             "Stored proof with hash: {} (expires at: {})",
             proof_hash, expires_at
         );
+        result
+    }
+
+    /// Marks a live proof as revoked and sets its status list bit. Revoking
+    /// an already revoked proof keeps the original revocation.
+    pub async fn revoke_proof(
+        &self,
+        proof_hash: &str,
+        revoked_by: Revoker,
+        reason: Option<String>,
+    ) -> Option<VerificationResult> {
+        let mut proofs = self.proofs.write().unwrap();
+        let stored_proof = proofs
+            .get_mut(proof_hash)
+            .filter(|proof| proof.expires_at > Utc::now())?;
+        let status = stored_proof.verification_result.status.as_mut()?;
+
+        if status.state == ProofState::Active {
+            status.state = ProofState::Revoked;
+            status.revoked_at = Some(Utc::now());
+            status.revoked_by = Some(revoked_by);
+            status.reason = reason;
+            self.status_list
+                .write()
+                .unwrap()
+                .revoked
+                .insert(status.status_list_index);
+            info!(
+                "Revoked proof {} (status list index {})",
+                proof_hash, status.status_list_index
+            );
+        }
+
+        Some(stored_proof.verification_result.clone())
+    }
+
+    /// The user's live proofs, most recently stored first, optionally for one
+    /// criterion.
+    pub async fn get_user_proofs(
+        &self,
+        username: &str,
+        verification_type: Option<VerificationType>,
+    ) -> Vec<VerificationResult> {
+        let user_index = self.user_index.read().unwrap();
+        let Some(by_type) = user_index.get(&username.to_ascii_lowercase()) else {
+            return Vec::new();
+        };
+
+        let proofs = self.proofs.read().unwrap();
+        let now = Utc::now();
+        let mut live: Vec<&StoredProof> = by_type
+            .iter()
+            .filter(|(indexed_type, _)| verification_type.is_none_or(|t| t == **indexed_type))
+            .flat_map(|(_, hashes)| hashes)
+            .filter_map(|hash| proofs.get(hash))
+            .filter(|proof| proof.expires_at > now)
+            .collect();
+        live.sort_by_key(|proof| std::cmp::Reverse(proof.created_at));
+        live.into_iter()
+            .map(|proof| proof.verification_result.clone())
+            .collect()
+    }
+
+    /// Deletes every proof issued for the user, expired or not, together with
+    /// their index and transparency log lookups. Their status list bits are
+    /// set, so copies held elsewhere no longer verify as active.
+    pub async fn erase_user(&self, username: &str) -> ErasedProofs {
+        let mut erased = ErasedProofs::default();
+        {
+            let mut proofs = self.proofs.write().unwrap();
+            let hashes: Vec<String> = proofs
+                .iter()
+                .filter(|(_, proof)| {
+                    proof
+                        .verification_result
+                        .username
+                        .eq_ignore_ascii_case(username)
+                })
+                .map(|(hash, _)| hash.clone())
+                .collect();
+            let mut status_list = self.status_list.write().unwrap();
+            for hash in hashes {
+                let Some(proof) = proofs.remove(&hash) else {
+                    continue;
+                };
+                if let Some(status) = proof.verification_result.status {
+                    status_list.revoked.insert(status.status_list_index);
+                    erased.revoked_status_indexes.push(status.status_list_index);
+                }
+                erased.proof_hashes.push(hash);
+            }
+        }
+        self.user_index
+            .write()
+            .unwrap()
+            .remove(&username.to_ascii_lowercase());
+        for hash in &erased.proof_hashes {
+            self.log.forget(hash);
+        }
+
+        erased.proof_hashes.sort();
+        erased.revoked_status_indexes.sort_unstable();
+        info!(
+            "Erased {} proofs for user: {}",
+            erased.proof_hashes.len(),
+            username
+        );
+        erased
+    }
+
+    /// The revocation bitstring of every proof issued so far, encoded for a
+    /// Bitstring Status List document.
+    pub async fn encoded_status_list(&self) -> String {
+        let status_list = self.status_list.read().unwrap();
+        status_list::encode_bitstring(&status_list.revoked, status_list.allocated)
     }
 
     pub async fn get_proof(&self, proof_hash: &str) -> Option<VerificationResult> {
@@ -72,6 +251,15 @@ impl ProofStorage {
         let after_count = proofs.len();
         if before_count != after_count {
             debug!("Cleaned up {} expired proofs", before_count - after_count);
+
+            let mut user_index = self.user_index.write().unwrap();
+            user_index.retain(|_, by_type| {
+                by_type.retain(|_, hashes| {
+                    hashes.retain(|hash| proofs.contains_key(hash));
+                    !hashes.is_empty()
+                });
+                !by_type.is_empty()
+            });
         }
     }
 
@@ -104,3 +292,122 @@ pub struct StorageStats {
     pub valid_proofs: usize,
     pub expired_proofs: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::{CriteriaOptions, DisclosureMode, VerificationType};
+
+    fn result(username: &str) -> VerificationResult {
+        VerificationResult {
+            username: username.to_string(),
+            verification_type: VerificationType::TotalStars,
+            threshold: 100,
+            options: CriteriaOptions::default(),
+            disclosure: DisclosureMode::default(),
+            meets_criteria: true,
+            evidence: None,
+            histogram: None,
+            anomaly_policy: None,
+            attestation_token: None,
+            attestation_claims: None,
+            nonce: None,
+            verified_at: Utc::now(),
+            proof_hash: None,
+            log_index: None,
+            status: None,
+        }
+    }
+
+    async fn store(
+        storage: &ProofStorage,
+        proof_hash: String,
+        result: VerificationResult,
+    ) -> VerificationResult {
+        let status_entry = storage.allocate_status_entry();
+        storage.store_proof(proof_hash, status_entry, result).await
+    }
+
+    #[tokio::test]
+    async fn test_get_user_proofs() {
+        let storage = ProofStorage::new();
+        store(&storage, "a".repeat(64), result("Octocat")).await;
+        let mut followers = result("octocat");
+        followers.verification_type = VerificationType::Followers;
+        followers.verified_at += Duration::seconds(1);
+        store(&storage, "b".repeat(64), followers).await;
+        store(&storage, "c".repeat(64), result("hubot")).await;
+
+        let proofs = storage.get_user_proofs("OCTOCAT", None).await;
+        assert_eq!(proofs.len(), 2);
+        assert_eq!(proofs[0].verification_type, VerificationType::Followers);
+
+        let stars = storage
+            .get_user_proofs("octocat", Some(VerificationType::TotalStars))
+            .await;
+        assert_eq!(stars.len(), 1);
+        assert_eq!(stars[0].username, "Octocat");
+        assert!(storage.get_user_proofs("nobody", None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_erase_user() {
+        let storage = ProofStorage::new();
+        store(&storage, "a".repeat(64), result("Octocat")).await;
+        store(&storage, "b".repeat(64), result("hubot")).await;
+        store(&storage, "c".repeat(64), result("octocat")).await;
+
+        let unrevoked = storage.encoded_status_list().await;
+        let erased = storage.erase_user("OctoCat").await;
+        assert_eq!(erased.proof_hashes, vec!["a".repeat(64), "c".repeat(64)]);
+        assert_eq!(erased.revoked_status_indexes, vec![0, 2]);
+        assert_ne!(storage.encoded_status_list().await, unrevoked);
+        assert!(storage.get_proof(&"a".repeat(64)).await.is_none());
+        assert!(storage.get_user_proofs("octocat", None).await.is_empty());
+        assert!(storage
+            .log()
+            .inclusion_proof(&"c".repeat(64), None)
+            .is_err());
+
+        // Other users' proofs and the log itself are untouched
+        assert!(storage.get_proof(&"b".repeat(64)).await.is_some());
+        assert!(storage.log().inclusion_proof(&"b".repeat(64), None).is_ok());
+        assert_eq!(storage.log().head().tree_size, 3);
+        assert_eq!(storage.erase_user("octocat").await, ErasedProofs::default());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_proof() {
+        let storage = ProofStorage::new();
+        let first = store(&storage, "a".repeat(64), result("octocat")).await;
+        let second = store(&storage, "b".repeat(64), result("octocat")).await;
+        assert_eq!(first.status.unwrap().status_list_index, 0);
+        assert_eq!(second.status.unwrap().status_list_index, 1);
+        assert_eq!(second.log_index, Some(1));
+        assert_eq!(storage.log().head().tree_size, 2);
+        let unrevoked = storage.encoded_status_list().await;
+
+        let revoked = storage
+            .revoke_proof(&"b".repeat(64), Revoker::Subject, Some("moved".to_string()))
+            .await
+            .unwrap();
+        let status = revoked.status.unwrap();
+        assert_eq!(status.state, ProofState::Revoked);
+        assert_eq!(status.revoked_by, Some(Revoker::Subject));
+
+        // Revoking again keeps the original revocation
+        let again = storage
+            .revoke_proof(&"b".repeat(64), Revoker::Operator, None)
+            .await
+            .unwrap();
+        assert_eq!(again.status.unwrap().revoked_by, Some(Revoker::Subject));
+
+        let stored = storage.get_proof(&"b".repeat(64)).await.unwrap();
+        assert_eq!(stored.status.unwrap().state, ProofState::Revoked);
+        assert_ne!(storage.encoded_status_list().await, unrevoked);
+        assert!(storage
+            .revoke_proof(&"c".repeat(64), Revoker::Operator, None)
+            .await
+            .is_none());
+    }
+}
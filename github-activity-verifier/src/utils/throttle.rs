@@ -0,0 +1,98 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Limits how often the same key (a username and criterion) may be
+/// verified within a sliding window, so thresholds can't be binary-searched
+/// through repeated requests.
+///
+/// Keys aren't tied to the caller, so anyone can use up a user's allowance
+/// and lock them out of a criterion for the rest of the window. That is the
+/// price of stopping probing by callers who rotate addresses; requests that
+/// fail on GitHub's side give their slot back with `release`, so outages
+/// don't add to it.
+#[derive(Debug, Clone)]
+pub struct RequestThrottle {
+    limit: usize,
+    window: Duration,
+    requests: Arc<Mutex<HashMap<String, VecDeque<DateTime<Utc>>>>>,
+}
+
+impl RequestThrottle {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            requests: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Records a request for `key`, unless the key already used up its
+    /// requests within the window.
+    pub fn try_acquire(&self, key: &str) -> bool {
+        let now = Utc::now();
+        let mut requests = self.requests.lock().unwrap();
+
+        // Drop keys whose requests all left the window while we hold the lock
+        requests.retain(|_, times| {
+            while times.front().is_some_and(|&time| now - time >= self.window) {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+
+        let times = requests.entry(key.to_string()).or_default();
+        if times.len() >= self.limit {
+            warn!("Throttling verification requests for {}", key);
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+
+    /// Gives back the most recent request of `key`, for requests that failed
+    /// before anything was learned about the user.
+    pub fn release(&self, key: &str) {
+        let mut requests = self.requests.lock().unwrap();
+        if let Some(times) = requests.get_mut(key) {
+            times.pop_back();
+            if times.is_empty() {
+                requests.remove(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_acquire() {
+        let throttle = RequestThrottle::new(2, Duration::hours(1));
+        assert!(throttle.try_acquire("octocat:total_stars"));
+        assert!(throttle.try_acquire("octocat:total_stars"));
+        assert!(!throttle.try_acquire("octocat:total_stars"));
+        // Other criteria and users have their own allowance
+        assert!(throttle.try_acquire("octocat:followers"));
+        assert!(throttle.try_acquire("hubot:total_stars"));
+
+        throttle.release("octocat:total_stars");
+        assert!(throttle.try_acquire("octocat:total_stars"));
+        assert!(!throttle.try_acquire("octocat:total_stars"));
+        throttle.release("nobody:total_stars");
+
+        let expired = RequestThrottle::new(1, Duration::zero());
+        assert!(expired.try_acquire("octocat:total_stars"));
+        assert!(expired.try_acquire("octocat:total_stars"));
+    }
+}
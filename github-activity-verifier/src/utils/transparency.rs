@@ -0,0 +1,376 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+type Hash = [u8; 32];
+
+#[derive(Debug, Error)]
+pub enum LogError {
+    #[error("Proof hash {0} is not in the log")]
+    UnknownLeaf(String),
+
+    #[error("Invalid tree size: {0}")]
+    InvalidTreeSize(String),
+}
+
+/// Size and root of the log at some point in time.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TreeHead {
+    pub tree_size: u64,
+    pub root_hash: String,
+}
+
+impl TreeHead {
+    /// The runtime data attested for this head.
+    pub fn attestation_data(&self) -> String {
+        format!("log:{}:{}", self.tree_size, self.root_hash)
+    }
+}
+
+/// A tree head bound to an enclave attestation token.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedTreeHead {
+    #[serde(flatten)]
+    pub head: TreeHead,
+    pub timestamp: DateTime<Utc>,
+    pub attestation_token: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    pub tree_size: u64,
+    pub root_hash: String,
+    pub audit_path: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsistencyProof {
+    pub first: TreeHead,
+    pub second: TreeHead,
+    pub proof: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct LogState {
+    leaves: Vec<Hash>,
+    index: HashMap<String, u64>,
+    signed_head: Option<SignedTreeHead>,
+}
+
+/// Append-only Merkle log (RFC 9162) of every proof hash issued. Leaves are
+/// `SHA-256(0x00 || proof_hash)` over the hex proof hash, inner nodes
+/// `SHA-256(0x01 || left || right)`.
+#[derive(Debug, Clone, Default)]
+pub struct TransparencyLog {
+    state: Arc<RwLock<LogState>>,
+}
+
+impl TransparencyLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a proof hash and returns its leaf index.
+    pub fn append(&self, proof_hash: &str) -> u64 {
+        let mut state = self.state.write().unwrap();
+        let index = state.leaves.len() as u64;
+        state.leaves.push(leaf_hash(proof_hash));
+        state.index.entry(proof_hash.to_string()).or_insert(index);
+        index
+    }
+
+    /// Drops the lookup from a proof hash to its leaf. The leaf itself stays,
+    /// so the tree and every head signed so far remain valid.
+    pub fn forget(&self, proof_hash: &str) -> bool {
+        self.state
+            .write()
+            .unwrap()
+            .index
+            .remove(proof_hash)
+            .is_some()
+    }
+
+    pub fn head(&self) -> TreeHead {
+        let state = self.state.read().unwrap();
+        tree_head(&state.leaves)
+    }
+
+    pub fn signed_head(&self) -> Option<SignedTreeHead> {
+        self.state.read().unwrap().signed_head.clone()
+    }
+
+    pub fn record_signed_head(&self, signed_head: SignedTreeHead) {
+        self.state.write().unwrap().signed_head = Some(signed_head);
+    }
+
+    /// Audit path of a proof hash in the tree of `tree_size` leaves
+    /// (default: the current tree).
+    pub fn inclusion_proof(
+        &self,
+        proof_hash: &str,
+        tree_size: Option<u64>,
+    ) -> Result<InclusionProof, LogError> {
+        let state = self.state.read().unwrap();
+        let tree_size = tree_size.unwrap_or(state.leaves.len() as u64);
+        let leaf_index = *state
+            .index
+            .get(proof_hash)
+            .ok_or_else(|| LogError::UnknownLeaf(proof_hash.to_string()))?;
+        if tree_size > state.leaves.len() as u64 || leaf_index >= tree_size {
+            return Err(LogError::InvalidTreeSize(format!(
+                "leaf {} is not in a tree of size {}",
+                leaf_index, tree_size
+            )));
+        }
+
+        let leaves = &state.leaves[..tree_size as usize];
+        Ok(InclusionProof {
+            leaf_index,
+            tree_size,
+            root_hash: hex(&root(leaves)),
+            audit_path: path(leaf_index as usize, leaves).iter().map(hex).collect(),
+        })
+    }
+
+    /// Proof that the tree of `first` leaves is a prefix of the tree of
+    /// `second` leaves.
+    pub fn consistency_proof(&self, first: u64, second: u64) -> Result<ConsistencyProof, LogError> {
+        let state = self.state.read().unwrap();
+        if first == 0 || first > second || second > state.leaves.len() as u64 {
+            return Err(LogError::InvalidTreeSize(format!(
+                "need 0 < first <= second <= {}",
+                state.leaves.len()
+            )));
+        }
+
+        let leaves = &state.leaves[..second as usize];
+        let proof = if first == second {
+            Vec::new()
+        } else {
+            subproof(first as usize, leaves, true)
+        };
+        Ok(ConsistencyProof {
+            first: tree_head(&leaves[..first as usize]),
+            second: tree_head(leaves),
+            proof: proof.iter().map(hex).collect(),
+        })
+    }
+}
+
+fn tree_head(leaves: &[Hash]) -> TreeHead {
+    TreeHead {
+        tree_size: leaves.len() as u64,
+        root_hash: hex(&root(leaves)),
+    }
+}
+
+fn leaf_hash(proof_hash: &str) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(proof_hash.as_bytes());
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The largest power of two smaller than `n` (for `n > 1`).
+fn split(n: usize) -> usize {
+    let mut k = 1;
+    while k * 2 < n {
+        k *= 2;
+    }
+    k
+}
+
+fn root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Sha256::digest([]).into(),
+        1 => leaves[0],
+        n => {
+            let k = split(n);
+            node_hash(&root(&leaves[..k]), &root(&leaves[k..]))
+        }
+    }
+}
+
+fn path(index: usize, leaves: &[Hash]) -> Vec<Hash> {
+    if leaves.len() <= 1 {
+        return Vec::new();
+    }
+    let k = split(leaves.len());
+    if index < k {
+        let mut path = path(index, &leaves[..k]);
+        path.push(root(&leaves[k..]));
+        path
+    } else {
+        let mut path = path(index - k, &leaves[k..]);
+        path.push(root(&leaves[..k]));
+        path
+    }
+}
+
+fn subproof(m: usize, leaves: &[Hash], complete: bool) -> Vec<Hash> {
+    let n = leaves.len();
+    if m == n {
+        return if complete {
+            Vec::new()
+        } else {
+            vec![root(leaves)]
+        };
+    }
+    let k = split(n);
+    if m <= k {
+        let mut proof = subproof(m, &leaves[..k], complete);
+        proof.push(root(&leaves[k..]));
+        proof
+    } else {
+        let mut proof = subproof(m - k, &leaves[k..], false);
+        proof.push(root(&leaves[..k]));
+        proof
+    }
+}
+
+fn hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(value: &str) -> Hash {
+        let bytes: Vec<u8> = (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    // RFC 9162, section 2.1.3.2
+    fn verify_inclusion(proof: &InclusionProof, proof_hash: &str) -> bool {
+        let (mut f, mut s) = (proof.leaf_index, proof.tree_size - 1);
+        let mut r = leaf_hash(proof_hash);
+        for p in proof.audit_path.iter().map(|p| unhex(p)) {
+            if s == 0 {
+                return false;
+            }
+            if f & 1 == 1 || f == s {
+                r = node_hash(&p, &r);
+                while f & 1 == 0 && f != 0 {
+                    f >>= 1;
+                    s >>= 1;
+                }
+            } else {
+                r = node_hash(&r, &p);
+            }
+            f >>= 1;
+            s >>= 1;
+        }
+        s == 0 && hex(&r) == proof.root_hash
+    }
+
+    // RFC 9162, section 2.1.4.2
+    fn verify_consistency(proof: &ConsistencyProof) -> bool {
+        let (first, second) = (proof.first.tree_size, proof.second.tree_size);
+        if first == second {
+            return proof.proof.is_empty() && proof.first.root_hash == proof.second.root_hash;
+        }
+        let mut path: Vec<Hash> = proof.proof.iter().map(|p| unhex(p)).collect();
+        if first.is_power_of_two() {
+            path.insert(0, unhex(&proof.first.root_hash));
+        }
+        let (mut f, mut s) = (first - 1, second - 1);
+        while f & 1 == 1 {
+            f >>= 1;
+            s >>= 1;
+        }
+        let (mut fr, mut sr) = (path[0], path[0]);
+        for c in &path[1..] {
+            if s == 0 {
+                return false;
+            }
+            if f & 1 == 1 || f == s {
+                fr = node_hash(c, &fr);
+                sr = node_hash(c, &sr);
+                while f & 1 == 0 && f != 0 {
+                    f >>= 1;
+                    s >>= 1;
+                }
+            } else {
+                sr = node_hash(&sr, c);
+            }
+            f >>= 1;
+            s >>= 1;
+        }
+        s == 0 && hex(&fr) == proof.first.root_hash && hex(&sr) == proof.second.root_hash
+    }
+
+    fn proof_hash(i: u64) -> String {
+        format!("{:x}", Sha256::digest(i.to_string()))
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let log = TransparencyLog::new();
+        assert_eq!(log.head().tree_size, 0);
+
+        for i in 0..13 {
+            assert_eq!(log.append(&proof_hash(i)), i);
+        }
+        for size in 1..=13 {
+            for i in 0..size {
+                let proof = log.inclusion_proof(&proof_hash(i), Some(size)).unwrap();
+                assert!(
+                    verify_inclusion(&proof, &proof_hash(i)),
+                    "{} in {}",
+                    i,
+                    size
+                );
+                assert!(!verify_inclusion(&proof, &proof_hash(i + 1)));
+            }
+        }
+        assert_eq!(
+            log.inclusion_proof(&proof_hash(0), None).unwrap().root_hash,
+            log.head().root_hash
+        );
+        assert!(log.inclusion_proof(&proof_hash(20), None).is_err());
+        assert!(log.inclusion_proof(&proof_hash(5), Some(5)).is_err());
+        assert!(log.inclusion_proof(&proof_hash(5), Some(14)).is_err());
+
+        let head = log.head();
+        assert!(log.forget(&proof_hash(3)));
+        assert!(!log.forget(&proof_hash(3)));
+        assert!(matches!(
+            log.inclusion_proof(&proof_hash(3), None),
+            Err(LogError::UnknownLeaf(_))
+        ));
+        assert_eq!(log.head(), head);
+    }
+
+    #[test]
+    fn test_consistency_proofs() {
+        let log = TransparencyLog::new();
+        for i in 0..13 {
+            log.append(&proof_hash(i));
+        }
+        for second in 1..=13 {
+            for first in 1..=second {
+                let proof = log.consistency_proof(first, second).unwrap();
+                assert!(verify_consistency(&proof), "{} -> {}", first, second);
+            }
+        }
+        assert!(log.consistency_proof(0, 3).is_err());
+        assert!(log.consistency_proof(4, 3).is_err());
+        assert!(log.consistency_proof(3, 14).is_err());
+    }
+}
@@ -1,6 +1,14 @@
+use crate::api::types::{CriteriaOptions, TimeWindow, VerificationType};
 use crate::utils::errors::AppError;
+use chrono::{Datelike, NaiveDate, Utc};
 use regex::Regex;
 
+pub const MAX_WINDOW_DAYS: u32 = 3650;
+// GitHub launched in 2008; nothing can be older
+pub const EARLIEST_WINDOW_YEAR: i32 = 2008;
+pub const MAX_SCOPED_REPOS: usize = 10;
+pub const MAX_SAMPLE_SIZE: u32 = 500;
+
 pub fn validate_github_username(username: &str) -> Result<(), AppError> {
     if username.is_empty() || username.len() > 39 {
         return Err(AppError::Validation(
@@ -28,9 +36,332 @@ pub fn validate_github_username(username: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Organization logins follow the same rules as user logins.
+pub fn validate_organization_name(org: &str) -> Result<(), AppError> {
+    validate_github_username(org)
+        .map_err(|_| AppError::Validation(format!("Invalid GitHub organization name '{}'", org)))
+}
+
+/// Validates an `owner/name` repository reference.
+pub fn validate_repository_name(repo: &str) -> Result<(), AppError> {
+    let invalid = || {
+        AppError::Validation(format!(
+            "Invalid repository '{}', expected owner/name",
+            repo
+        ))
+    };
+
+    let (owner, name) = repo.split_once('/').ok_or_else(invalid)?;
+    validate_github_username(owner).map_err(|_| invalid())?;
+
+    let regex = Regex::new(r"^[A-Za-z0-9._-]{1,100}$").expect("Invalid regex pattern");
+    if !regex.is_match(name) || name == "." || name == ".." {
+        return Err(invalid());
+    }
+
+    Ok(())
+}
+
+/// Language names as GitHub reports them, e.g. `Rust`, `C++` or `Jupyter Notebook`.
+pub fn validate_language_name(language: &str) -> Result<(), AppError> {
+    let regex = Regex::new(r"^[A-Za-z0-9+#.' -]{1,50}$").expect("Invalid regex pattern");
+    if !regex.is_match(language) || language.trim().is_empty() {
+        return Err(AppError::Validation(format!(
+            "Invalid language name '{}'",
+            language
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn validate_time_window(window: &TimeWindow, today: NaiveDate) -> Result<(), AppError> {
+    let earliest = NaiveDate::from_ymd_opt(EARLIEST_WINDOW_YEAR, 1, 1).unwrap();
+
+    match *window {
+        TimeWindow::Rolling { days } => {
+            if days == 0 || days > MAX_WINDOW_DAYS {
+                return Err(AppError::Validation(format!(
+                    "Rolling window must be between 1 and {} days",
+                    MAX_WINDOW_DAYS
+                )));
+            }
+        }
+        TimeWindow::CalendarYear { year } => {
+            if year < EARLIEST_WINDOW_YEAR || year > today.year() {
+                return Err(AppError::Validation(format!(
+                    "Calendar year must be between {} and {}",
+                    EARLIEST_WINDOW_YEAR,
+                    today.year()
+                )));
+            }
+        }
+        TimeWindow::Range { from, to } => {
+            if from > to {
+                return Err(AppError::Validation(
+                    "Window 'from' must not be after 'to'".to_string(),
+                ));
+            }
+            if from < earliest || to > today {
+                return Err(AppError::Validation(format!(
+                    "Window must lie between {} and today",
+                    earliest
+                )));
+            }
+            if (to - from).num_days() >= MAX_WINDOW_DAYS as i64 {
+                return Err(AppError::Validation(format!(
+                    "Window must not be longer than {} days",
+                    MAX_WINDOW_DAYS
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn parse_time_zone(time_zone: &str) -> Result<chrono_tz::Tz, AppError> {
+    time_zone
+        .parse()
+        .map_err(|_| AppError::Validation(format!("Unknown time zone '{}'", time_zone)))
+}
+
+pub fn validate_criteria_options(
+    verification_type: VerificationType,
+    options: &CriteriaOptions,
+) -> Result<(), AppError> {
+    // An option the criterion doesn't read would still be bound into the
+    // proof, which would then claim a check that never happened
+    let unsupported = unsupported_options(verification_type, options);
+    if !unsupported.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Option(s) {} don't apply to this verification type",
+            unsupported.join(", ")
+        )));
+    }
+
+    if let Some(window) = &options.window {
+        validate_time_window(window, Utc::now().date_naive())?;
+    }
+
+    if let Some(min_repo_age_days) = options.min_repo_age_days {
+        if min_repo_age_days == 0 || min_repo_age_days > MAX_WINDOW_DAYS {
+            return Err(AppError::Validation(format!(
+                "min_repo_age_days must be between 1 and {}",
+                MAX_WINDOW_DAYS
+            )));
+        }
+    }
+
+    if let Some(time_zone) = &options.time_zone {
+        parse_time_zone(time_zone)?;
+    }
+
+    // Histograms are built from the events feed that these criteria read
+    if options.histogram.is_some()
+        && !matches!(
+            verification_type,
+            VerificationType::YearlyCommits | VerificationType::ConsecutiveDays
+        )
+    {
+        return Err(AppError::Validation(
+            "histogram is only available for yearly_commits and consecutive_days".to_string(),
+        ));
+    }
+
+    if let Some(sample_size) = options.sample_size {
+        if sample_size == 0 || sample_size > MAX_SAMPLE_SIZE {
+            return Err(AppError::Validation(format!(
+                "sample_size must be between 1 and {}",
+                MAX_SAMPLE_SIZE
+            )));
+        }
+    }
+
+    if let Some(org) = &options.org {
+        validate_organization_name(org)?;
+    }
+    if let Some(repo) = &options.repo {
+        validate_repository_name(repo)?;
+    }
+
+    if let Some(language) = &options.language {
+        validate_language_name(language)?;
+    }
+
+    if options.repos.len() > MAX_SCOPED_REPOS {
+        return Err(AppError::Validation(format!(
+            "At most {} repositories can be given in 'repos'",
+            MAX_SCOPED_REPOS
+        )));
+    }
+    for repo in &options.repos {
+        validate_repository_name(repo)?;
+    }
+
+    match verification_type {
+        VerificationType::OrgMembership if options.org.is_none() => Err(AppError::Validation(
+            "org_membership requires the 'org' option".to_string(),
+        )),
+        VerificationType::RepoPermission if options.repo.is_none() => Err(AppError::Validation(
+            "repo_permission requires the 'repo' option".to_string(),
+        )),
+        VerificationType::RepoCommits if options.repos.is_empty() => Err(AppError::Validation(
+            "repo_commits requires at least one repository in 'repos'".to_string(),
+        )),
+        VerificationType::LanguageRepos | VerificationType::LanguageBytes
+            if options.language.is_none() =>
+        {
+            Err(AppError::Validation(
+                "Language criteria require the 'language' option".to_string(),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The options set in `options` that `verification_type` doesn't read.
+/// `histogram` is checked separately.
+fn unsupported_options(
+    verification_type: VerificationType,
+    options: &CriteriaOptions,
+) -> Vec<&'static str> {
+    use VerificationType::*;
+
+    let t = verification_type;
+    let filters_repos = matches!(t, TotalStars | PublicRepos);
+    // (option, whether it is set, whether the criterion reads it)
+    let checks = [
+        ("window", options.window.is_some(), t.is_time_based()),
+        (
+            "exclude_own_repos",
+            options.exclude_own_repos,
+            matches!(t, MergedPullRequests | OpenedIssues | CodeReviews),
+        ),
+        (
+            "substantive_reviews_only",
+            options.substantive_reviews_only,
+            t == CodeReviews,
+        ),
+        ("org", options.org.is_some(), t == OrgMembership),
+        ("repo", options.repo.is_some(), t == RepoPermission),
+        (
+            "permission",
+            options.permission.is_some(),
+            t == RepoPermission,
+        ),
+        (
+            "repos",
+            !options.repos.is_empty(),
+            matches!(
+                t,
+                YearlyCommits
+                    | ConsecutiveDays
+                    | MergedPullRequests
+                    | OpenedIssues
+                    | CodeReviews
+                    | RepoCommits
+                    | SignedCommits
+            ),
+        ),
+        (
+            "language",
+            options.language.is_some(),
+            matches!(t, LanguageRepos | LanguageBytes),
+        ),
+        (
+            "exclude_forks",
+            options.exclude_forks.is_some(),
+            filters_repos || matches!(t, LanguageRepos | LanguageBytes),
+        ),
+        ("exclude_archived", options.exclude_archived, filters_repos),
+        (
+            "exclude_templates",
+            options.exclude_templates,
+            filters_repos,
+        ),
+        (
+            "exclude_repos_without_commits",
+            options.exclude_repos_without_commits,
+            filters_repos,
+        ),
+        (
+            "min_repo_age_days",
+            options.min_repo_age_days.is_some(),
+            filters_repos,
+        ),
+        (
+            "time_zone",
+            options.time_zone.is_some(),
+            t == ConsecutiveDays,
+        ),
+        ("streak", options.streak.is_some(), t == ConsecutiveDays),
+        (
+            "contribution_events_only",
+            options.contribution_events_only,
+            t == ConsecutiveDays,
+        ),
+        (
+            "sample_size",
+            options.sample_size.is_some(),
+            t == SignedCommits,
+        ),
+    ];
+
+    checks
+        .into_iter()
+        .filter(|&(_, set, supported)| set && !supported)
+        .map(|(name, _, _)| name)
+        .collect()
+}
+
+pub fn is_valid_proof_hash(proof_hash: &str) -> bool {
+    proof_hash.len() == 64 && proof_hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Nonces must be long enough to be unguessable and safe to embed in JSON
+/// and URLs: 16 to 128 base64url or hex characters.
+pub fn validate_nonce(nonce: &str) -> Result<(), AppError> {
+    let regex = Regex::new(r"^[A-Za-z0-9_-]{16,128}$").expect("Invalid regex pattern");
+    if !regex.is_match(nonce) {
+        return Err(AppError::Validation(
+            "nonce must be 16 to 128 characters of A-Z, a-z, 0-9, '-' or '_'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_callback_url(callback_url: &str, allowed_hosts: &[String]) -> Result<(), AppError> {
+    let url = reqwest::Url::parse(callback_url)
+        .map_err(|_| AppError::Validation("Invalid callback URL".to_string()))?;
+
+    if url.scheme() != "https" {
+        return Err(AppError::Validation(
+            "Callback URL must use https".to_string(),
+        ));
+    }
+
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(AppError::Validation(
+            "Callback URL must not contain credentials".to_string(),
+        ));
+    }
+
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    if !allowed_hosts.contains(&host) {
+        return Err(AppError::Validation(format!(
+            "Callback host '{}' is not in the allow-list",
+            host
+        )));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::verification::histogram::HistogramGranularity;
 
     #[test]
     fn test_valid_usernames() {
@@ -50,4 +381,196 @@ mod tests {
         assert!(validate_github_username("user@invalid").is_err());
         assert!(validate_github_username(&"a".repeat(40)).is_err());
     }
+
+    #[test]
+    fn test_callback_urls() {
+        let allowed = vec!["hooks.example.com".to_string()];
+        assert!(validate_callback_url("https://hooks.example.com/notify", &allowed).is_ok());
+        assert!(validate_callback_url("https://HOOKS.example.com/notify", &allowed).is_ok());
+
+        assert!(validate_callback_url("http://hooks.example.com/notify", &allowed).is_err());
+        assert!(validate_callback_url("https://evil.example.com/notify", &allowed).is_err());
+        assert!(validate_callback_url("https://user:pw@hooks.example.com/", &allowed).is_err());
+        assert!(validate_callback_url("not a url", &allowed).is_err());
+        assert!(validate_callback_url("https://hooks.example.com/notify", &[]).is_err());
+    }
+
+    #[test]
+    fn test_nonces() {
+        assert!(validate_nonce("3q2-7wAAAAAAAAAA").is_ok());
+        assert!(validate_nonce(&"f".repeat(128)).is_ok());
+
+        assert!(validate_nonce("too-short").is_err());
+        assert!(validate_nonce(&"f".repeat(129)).is_err());
+        assert!(validate_nonce("has spaces in the nonce").is_err());
+        assert!(validate_nonce("padded+base64/nonce==").is_err());
+    }
+
+    #[test]
+    fn test_criteria_options() {
+        let commits = VerificationType::YearlyCommits;
+        assert!(validate_criteria_options(commits, &CriteriaOptions::default()).is_ok());
+
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Rolling { days: 730 }),
+            ..Default::default()
+        };
+        assert!(validate_criteria_options(commits, &options).is_ok());
+
+        options.window = Some(TimeWindow::Rolling { days: 0 });
+        assert!(validate_criteria_options(commits, &options).is_err());
+        options.window = Some(TimeWindow::Rolling {
+            days: MAX_WINDOW_DAYS + 1,
+        });
+        assert!(validate_criteria_options(commits, &options).is_err());
+
+        options.window = None;
+        let stars = VerificationType::TotalStars;
+        options.min_repo_age_days = Some(30);
+        assert!(validate_criteria_options(stars, &options).is_ok());
+        options.min_repo_age_days = Some(0);
+        assert!(validate_criteria_options(stars, &options).is_err());
+
+        options.min_repo_age_days = None;
+        options.histogram = Some(HistogramGranularity::Week);
+        assert!(validate_criteria_options(commits, &options).is_ok());
+        assert!(validate_criteria_options(VerificationType::TotalStars, &options).is_err());
+
+        options.histogram = None;
+        let signed = VerificationType::SignedCommits;
+        options.sample_size = Some(MAX_SAMPLE_SIZE);
+        assert!(validate_criteria_options(signed, &options).is_ok());
+        options.sample_size = Some(MAX_SAMPLE_SIZE + 1);
+        assert!(validate_criteria_options(signed, &options).is_err());
+
+        options.sample_size = None;
+        let streak = VerificationType::ConsecutiveDays;
+        options.time_zone = Some("Asia/Tokyo".to_string());
+        assert!(validate_criteria_options(streak, &options).is_ok());
+        options.time_zone = Some("Mars/Olympus_Mons".to_string());
+        assert!(validate_criteria_options(streak, &options).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_options() {
+        let repos = CriteriaOptions {
+            repos: vec!["rust-lang/rust".to_string()],
+            ..Default::default()
+        };
+        for verification_type in [
+            VerificationType::YearlyCommits,
+            VerificationType::ConsecutiveDays,
+            VerificationType::OpenedIssues,
+        ] {
+            assert!(validate_criteria_options(verification_type, &repos).is_ok());
+        }
+        assert!(validate_criteria_options(VerificationType::TotalStars, &repos).is_err());
+
+        let window = CriteriaOptions {
+            window: Some(TimeWindow::Rolling { days: 30 }),
+            ..Default::default()
+        };
+        assert!(validate_criteria_options(VerificationType::Followers, &window).is_err());
+
+        let filters = CriteriaOptions {
+            exclude_forks: Some(true),
+            exclude_archived: true,
+            language: Some("Rust".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            unsupported_options(VerificationType::LanguageRepos, &filters),
+            vec!["exclude_archived"]
+        );
+        assert_eq!(
+            unsupported_options(VerificationType::TotalStars, &filters),
+            vec!["language"]
+        );
+        assert_eq!(
+            unsupported_options(VerificationType::YearlyCommits, &filters),
+            vec!["language", "exclude_forks", "exclude_archived"]
+        );
+    }
+
+    #[test]
+    fn test_time_windows() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+
+        assert!(validate_time_window(&TimeWindow::CalendarYear { year: 2025 }, today).is_ok());
+        assert!(validate_time_window(&TimeWindow::CalendarYear { year: 2026 }, today).is_ok());
+        assert!(validate_time_window(&TimeWindow::CalendarYear { year: 2027 }, today).is_err());
+        assert!(validate_time_window(&TimeWindow::CalendarYear { year: 2007 }, today).is_err());
+
+        let range = |from: &str, to: &str| TimeWindow::Range {
+            from: date(from),
+            to: date(to),
+        };
+        assert!(validate_time_window(&range("2025-01-01", "2025-06-30"), today).is_ok());
+        assert!(validate_time_window(&range("2025-06-30", "2025-01-01"), today).is_err());
+        assert!(validate_time_window(&range("2026-01-01", "2026-12-31"), today).is_err());
+        assert!(validate_time_window(&range("2010-01-01", "2025-01-01"), today).is_err());
+    }
+
+    #[test]
+    fn test_membership_options() {
+        let mut options = CriteriaOptions::default();
+        assert!(validate_criteria_options(VerificationType::OrgMembership, &options).is_err());
+        assert!(validate_criteria_options(VerificationType::RepoPermission, &options).is_err());
+
+        let org = CriteriaOptions {
+            org: Some("rust-lang".to_string()),
+            ..Default::default()
+        };
+        let repo = CriteriaOptions {
+            repo: Some("rust-lang/rust.vim".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_criteria_options(VerificationType::OrgMembership, &org).is_ok());
+        assert!(validate_criteria_options(VerificationType::RepoPermission, &repo).is_ok());
+        assert!(validate_criteria_options(VerificationType::RepoPermission, &org).is_err());
+
+        options.org = Some("../admin".to_string());
+        assert!(validate_criteria_options(VerificationType::OrgMembership, &options).is_err());
+
+        assert!(validate_repository_name("octocat").is_err());
+        assert!(validate_repository_name("octocat/..").is_err());
+        assert!(validate_repository_name("octocat/a/b").is_err());
+        assert!(validate_repository_name("-octocat/repo").is_err());
+    }
+
+    #[test]
+    fn test_language_options() {
+        let mut options = CriteriaOptions::default();
+        assert!(validate_criteria_options(VerificationType::LanguageBytes, &options).is_err());
+
+        for language in ["Rust", "C++", "C#", "Jupyter Notebook", "Objective-C"] {
+            options.language = Some(language.to_string());
+            assert!(
+                validate_criteria_options(VerificationType::LanguageRepos, &options).is_ok(),
+                "{}",
+                language
+            );
+        }
+
+        options.language = Some("Rust\n".to_string());
+        assert!(validate_criteria_options(VerificationType::LanguageRepos, &options).is_err());
+        options.language = Some(" ".to_string());
+        assert!(validate_criteria_options(VerificationType::LanguageRepos, &options).is_err());
+    }
+
+    #[test]
+    fn test_scoped_repos() {
+        let mut options = CriteriaOptions::default();
+        assert!(validate_criteria_options(VerificationType::RepoCommits, &options).is_err());
+
+        options.repos = vec!["rust-lang/rust".to_string(), "tokio-rs/tokio".to_string()];
+        assert!(validate_criteria_options(VerificationType::RepoCommits, &options).is_ok());
+
+        options.repos.push("not a repo".to_string());
+        assert!(validate_criteria_options(VerificationType::RepoCommits, &options).is_err());
+
+        options.repos = vec!["octocat/repo".to_string(); MAX_SCOPED_REPOS + 1];
+        assert!(validate_criteria_options(VerificationType::RepoCommits, &options).is_err());
+    }
 }
@@ -0,0 +1,488 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::github::GitHubUserRepo;
+
+// A burst is this many trivial commits to one repository within an hour
+const BURST_MIN_COMMITS: usize = 10;
+const BURST_WINDOW_MINUTES: i64 = 60;
+// Commits authored this long before they were pushed are considered
+// backdated; a handful of them (e.g. an old branch) is not suspicious
+const BACKDATE_TOLERANCE_DAYS: i64 = 30;
+const BACKDATED_MIN_COMMITS: usize = 5;
+// Pushes whose intervals vary by less than 2% are machine-regular
+const REGULAR_MIN_INTERVALS: usize = 14;
+const REGULAR_MAX_VARIATION: f64 = 0.02;
+// A throwaway repository receives nearly all of a sizeable activity
+const THROWAWAY_MIN_COMMITS: usize = 30;
+const THROWAWAY_MIN_SHARE: f64 = 0.9;
+
+// Commit messages that carry no information on their own
+const TRIVIAL_MESSAGES: [&str; 12] = [
+    "update",
+    "updates",
+    "commit",
+    "wip",
+    "test",
+    "tmp",
+    "change",
+    "changes",
+    "empty",
+    "empty commit",
+    "auto commit",
+    "automated commit",
+];
+
+/// What happens to activity flagged by the heuristics, configured by the
+/// operator through `ANOMALY_POLICY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyPolicy {
+    /// Flagged commits are left out of the count
+    Exclude,
+    /// Any flagged activity fails the criterion
+    Downgrade,
+    /// Anomalies only appear in the evidence
+    #[default]
+    Report,
+    /// Activity isn't analyzed, which saves the extra GitHub requests for
+    /// author dates and repository metadata
+    Off,
+}
+
+impl AnomalyPolicy {
+    pub fn is_enabled(self) -> bool {
+        self != Self::Off
+    }
+}
+
+impl FromStr for AnomalyPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "exclude" => Ok(Self::Exclude),
+            "downgrade" => Ok(Self::Downgrade),
+            "report" => Ok(Self::Report),
+            "off" => Ok(Self::Off),
+            other => Err(format!("Unknown anomaly policy: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Many commits with contentless messages pushed within a short time
+    TrivialBurst,
+    /// Nearly all activity goes to a new, unstarred repository of the user
+    ThrowawayRepository,
+    /// Author dates far from the time the commits were pushed
+    BackdatedCommits,
+    /// Pushes at near-constant intervals, as a scheduled job would make
+    RegularTiming,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub repository: String,
+    pub commits: usize,
+    pub detail: String,
+}
+
+/// A commit as seen in a push event. The author date is only known for
+/// commits that could be looked up in the commits API.
+#[derive(Debug, Clone)]
+pub struct ActivityCommit {
+    pub sha: String,
+    pub repository: String,
+    pub message: String,
+    pub pushed_at: DateTime<Utc>,
+    pub authored_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+pub struct AnomalyReport {
+    pub anomalies: Vec<Anomaly>,
+    flagged: HashSet<String>,
+}
+
+impl AnomalyReport {
+    pub fn is_empty(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    pub fn is_flagged(&self, sha: &str) -> bool {
+        self.flagged.contains(sha)
+    }
+
+    pub fn to_evidence(&self, policy: AnomalyPolicy) -> serde_json::Value {
+        json!({
+            "policy": policy,
+            "flagged_commits": self.flagged.len(),
+            "anomalies": self.anomalies,
+        })
+    }
+
+    fn flag(&mut self, kind: AnomalyKind, repository: &str, shas: Vec<String>, detail: String) {
+        self.anomalies.push(Anomaly {
+            kind,
+            repository: repository.to_string(),
+            commits: shas.len(),
+            detail,
+        });
+        self.flagged.extend(shas);
+    }
+}
+
+/// Runs every heuristic over the pushed commits. `repos` holds the user's
+/// own repositories keyed by lowercase `owner/name`, and `window_start` is
+/// the start of the evaluated window.
+pub fn analyze(
+    commits: &[ActivityCommit],
+    repos: &HashMap<String, GitHubUserRepo>,
+    window_start: DateTime<Utc>,
+) -> AnomalyReport {
+    let mut by_repository: BTreeMap<&str, Vec<&ActivityCommit>> = BTreeMap::new();
+    for commit in commits {
+        by_repository
+            .entry(commit.repository.as_str())
+            .or_default()
+            .push(commit);
+    }
+
+    let mut report = AnomalyReport::default();
+    for (repository, mut repo_commits) in by_repository {
+        repo_commits.sort_by_key(|commit| commit.pushed_at);
+
+        detect_trivial_burst(&mut report, repository, &repo_commits);
+        detect_backdated_commits(&mut report, repository, &repo_commits);
+        detect_regular_timing(&mut report, repository, &repo_commits);
+        detect_throwaway_repository(
+            &mut report,
+            repository,
+            &repo_commits,
+            commits.len(),
+            repos,
+            window_start,
+        );
+    }
+    report
+}
+
+fn is_trivial_message(message: &str) -> bool {
+    let subject = message.lines().next().unwrap_or_default().trim();
+    subject.chars().filter(|c| c.is_alphanumeric()).count() < 3
+        || TRIVIAL_MESSAGES.contains(&subject.to_lowercase().trim_end_matches('.'))
+}
+
+fn detect_trivial_burst(report: &mut AnomalyReport, repository: &str, commits: &[&ActivityCommit]) {
+    let trivial: Vec<_> = commits
+        .iter()
+        .filter(|commit| is_trivial_message(&commit.message))
+        .collect();
+
+    let mut in_burst = BTreeSet::new();
+    let mut start = 0;
+    for end in 0..trivial.len() {
+        while trivial[end].pushed_at - trivial[start].pushed_at
+            > Duration::minutes(BURST_WINDOW_MINUTES)
+        {
+            start += 1;
+        }
+        if end + 1 - start >= BURST_MIN_COMMITS {
+            in_burst.extend(start..=end);
+        }
+    }
+
+    if !in_burst.is_empty() {
+        let shas = in_burst.iter().map(|&i| trivial[i].sha.clone()).collect();
+        report.flag(
+            AnomalyKind::TrivialBurst,
+            repository,
+            shas,
+            format!(
+                "{} or more commits with trivial messages within {} minutes",
+                BURST_MIN_COMMITS, BURST_WINDOW_MINUTES
+            ),
+        );
+    }
+}
+
+fn detect_backdated_commits(
+    report: &mut AnomalyReport,
+    repository: &str,
+    commits: &[&ActivityCommit],
+) {
+    let tolerance = Duration::days(BACKDATE_TOLERANCE_DAYS);
+    let backdated: Vec<String> = commits
+        .iter()
+        .filter(|commit| {
+            commit.authored_at.is_some_and(|authored_at| {
+                commit.pushed_at - authored_at > tolerance
+                    || authored_at - commit.pushed_at > Duration::days(1)
+            })
+        })
+        .map(|commit| commit.sha.clone())
+        .collect();
+
+    if backdated.len() >= BACKDATED_MIN_COMMITS {
+        report.flag(
+            AnomalyKind::BackdatedCommits,
+            repository,
+            backdated,
+            format!(
+                "author dates more than {} days before they were pushed, or in the future",
+                BACKDATE_TOLERANCE_DAYS
+            ),
+        );
+    }
+}
+
+fn detect_regular_timing(
+    report: &mut AnomalyReport,
+    repository: &str,
+    commits: &[&ActivityCommit],
+) {
+    // Commits pushed together share a push time
+    let pushes: BTreeSet<DateTime<Utc>> = commits.iter().map(|commit| commit.pushed_at).collect();
+    let pushes: Vec<_> = pushes.into_iter().collect();
+    let intervals: Vec<f64> = pushes
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_seconds() as f64)
+        .collect();
+    if intervals.len() < REGULAR_MIN_INTERVALS {
+        return;
+    }
+
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    if mean <= 0.0 {
+        return;
+    }
+    let variance = intervals
+        .iter()
+        .map(|interval| (interval - mean).powi(2))
+        .sum::<f64>()
+        / intervals.len() as f64;
+    let variation = variance.sqrt() / mean;
+
+    if variation < REGULAR_MAX_VARIATION {
+        report.flag(
+            AnomalyKind::RegularTiming,
+            repository,
+            commits.iter().map(|commit| commit.sha.clone()).collect(),
+            format!(
+                "{} pushes every {} seconds with {:.1}% variation",
+                pushes.len(),
+                mean.round(),
+                variation * 100.0
+            ),
+        );
+    }
+}
+
+fn detect_throwaway_repository(
+    report: &mut AnomalyReport,
+    repository: &str,
+    commits: &[&ActivityCommit],
+    total_commits: usize,
+    repos: &HashMap<String, GitHubUserRepo>,
+    window_start: DateTime<Utc>,
+) {
+    if total_commits < THROWAWAY_MIN_COMMITS
+        || (commits.len() as f64) < total_commits as f64 * THROWAWAY_MIN_SHARE
+    {
+        return;
+    }
+    let Some(repo) = repos.get(&repository.to_ascii_lowercase()) else {
+        return;
+    };
+    if repo.fork || repo.stargazers_count > 0 || repo.created_at < window_start {
+        return;
+    }
+
+    report.flag(
+        AnomalyKind::ThrowawayRepository,
+        repository,
+        commits.iter().map(|commit| commit.sha.clone()).collect(),
+        format!(
+            "{} of {} commits went to a repository created within the window without any stars",
+            commits.len(),
+            total_commits
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(
+        sha: &str,
+        repository: &str,
+        message: &str,
+        pushed_at: DateTime<Utc>,
+    ) -> ActivityCommit {
+        ActivityCommit {
+            sha: sha.to_string(),
+            repository: repository.to_string(),
+            message: message.to_string(),
+            pushed_at,
+            authored_at: Some(pushed_at),
+        }
+    }
+
+    fn repo(name: &str, stars: u32, created_at: DateTime<Utc>) -> GitHubUserRepo {
+        serde_json::from_value(json!({
+            "id": 1,
+            "name": name,
+            "stargazers_count": stars,
+            "created_at": created_at,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trivial_burst() {
+        let start = Utc::now() - Duration::days(10);
+        let mut commits: Vec<_> = (0..12)
+            .map(|i| {
+                commit(
+                    &format!("burst{}", i),
+                    "dev/app",
+                    if i % 2 == 0 { "." } else { "Update" },
+                    start + Duration::minutes(i * 3),
+                )
+            })
+            .collect();
+        commits.push(commit(
+            "real",
+            "dev/app",
+            "Fix token refresh race",
+            start + Duration::minutes(5),
+        ));
+
+        let report = analyze(&commits, &HashMap::new(), start - Duration::days(1));
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(report.anomalies[0].kind, AnomalyKind::TrivialBurst);
+        assert!(report.is_flagged("burst0"));
+        assert!(!report.is_flagged("real"));
+
+        // The same messages spread over days are not a burst
+        let spread: Vec<_> = (0..12)
+            .map(|i| {
+                commit(
+                    &format!("c{}", i),
+                    "dev/app",
+                    "wip",
+                    start + Duration::hours(i * 5),
+                )
+            })
+            .collect();
+        assert!(analyze(&spread, &HashMap::new(), start).is_empty());
+    }
+
+    #[test]
+    fn test_backdated_commits() {
+        let now = Utc::now();
+        let mut commits: Vec<_> = (0..6)
+            .map(|i| {
+                let mut commit = commit(&format!("old{}", i), "dev/graph", "Add pixel", now);
+                commit.authored_at = Some(now - Duration::days(200 + i));
+                commit
+            })
+            .collect();
+
+        let report = analyze(&commits, &HashMap::new(), now - Duration::days(365));
+        assert_eq!(report.anomalies[0].kind, AnomalyKind::BackdatedCommits);
+        assert_eq!(report.anomalies[0].commits, 6);
+
+        // A few rebased commits are tolerated
+        commits.truncate(BACKDATED_MIN_COMMITS - 1);
+        assert!(analyze(&commits, &HashMap::new(), now - Duration::days(365)).is_empty());
+    }
+
+    #[test]
+    fn test_regular_timing() {
+        let start = Utc::now() - Duration::days(30);
+        let scheduled: Vec<_> = (0..20)
+            .map(|i| {
+                let jitter = Duration::seconds((i * 37) % 120);
+                commit(
+                    &format!("bot{}", i),
+                    "dev/daily",
+                    "Daily update of the data file",
+                    start + Duration::days(i) + jitter,
+                )
+            })
+            .collect();
+        let report = analyze(&scheduled, &HashMap::new(), start);
+        assert_eq!(report.anomalies[0].kind, AnomalyKind::RegularTiming);
+        assert_eq!(report.anomalies[0].commits, 20);
+
+        let human: Vec<_> = (0..20)
+            .map(|i| {
+                let hours = (i * i * 7) % 23;
+                commit(
+                    &format!("h{}", i),
+                    "dev/app",
+                    "Refactor parser",
+                    start + Duration::days(i) + Duration::hours(hours),
+                )
+            })
+            .collect();
+        assert!(analyze(&human, &HashMap::new(), start).is_empty());
+    }
+
+    #[test]
+    fn test_throwaway_repository() {
+        let now = Utc::now();
+        let window_start = now - Duration::days(365);
+        let commits: Vec<_> = (0..40)
+            .map(|i| {
+                let repository = if i == 0 { "dev/real" } else { "dev/throwaway" };
+                commit(
+                    &format!("c{}", i),
+                    repository,
+                    "Change the config loader",
+                    now - Duration::hours(i * 29 + (i * i) % 11),
+                )
+            })
+            .collect();
+
+        let mut repos = HashMap::new();
+        repos.insert(
+            "dev/throwaway".to_string(),
+            repo("throwaway", 0, now - Duration::days(60)),
+        );
+        let report = analyze(&commits, &repos, window_start);
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(report.anomalies[0].kind, AnomalyKind::ThrowawayRepository);
+        assert!(!report.is_flagged("c0"));
+
+        // An established or starred repository is not a throwaway
+        repos.insert(
+            "dev/throwaway".to_string(),
+            repo("throwaway", 3, now - Duration::days(60)),
+        );
+        assert!(analyze(&commits, &repos, window_start).is_empty());
+        repos.insert(
+            "dev/throwaway".to_string(),
+            repo("throwaway", 0, now - Duration::days(900)),
+        );
+        assert!(analyze(&commits, &repos, window_start).is_empty());
+    }
+
+    #[test]
+    fn test_anomaly_policy_from_str() {
+        assert_eq!("exclude".parse(), Ok(AnomalyPolicy::Exclude));
+        assert_eq!("downgrade".parse(), Ok(AnomalyPolicy::Downgrade));
+        assert_eq!("report".parse(), Ok(AnomalyPolicy::Report));
+        assert_eq!("off".parse(), Ok(AnomalyPolicy::Off));
+        assert!("ignore".parse::<AnomalyPolicy>().is_err());
+    }
+}
@@ -1,158 +1,1605 @@
-use chrono::{Duration, NaiveDate, Utc};
-use std::collections::HashSet;
-use tracing::{debug, info};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
 
-use crate::api::types::VerificationType;
-use crate::github::{GitHubClient, GitHubEvent};
+use crate::api::types::{CriteriaOptions, ResolvedWindow, StreakMode, VerificationType};
+use crate::github::client::COMMITS_PER_PAGE;
+use crate::github::{
+    GitHubDataSource, GitHubError, GitHubEvent, GitHubReviewContribution,
+    GitHubReviewContributions, GitHubUser, GitHubUserRepo,
+};
 use crate::utils::errors::AppError;
+use crate::utils::progress::{ProgressReporter, VerificationPhase};
+use crate::utils::validation;
+use crate::verification::anomaly::{self, ActivityCommit, AnomalyPolicy, AnomalyReport};
+use crate::verification::histogram::{ActivityHistogram, DailyCounts};
+
+// Events that count as contributions for `contribution_events_only`
+const CONTRIBUTION_EVENT_TYPES: [&str; 4] = [
+    "PushEvent",
+    "PullRequestEvent",
+    "PullRequestReviewEvent",
+    "IssuesEvent",
+];
+// The search API never returns more than 1000 results
+const MAX_SEARCH_PAGES: u32 = 10;
+const SEARCH_PAGE_SIZE: u32 = 100;
+const MAX_COMMIT_PAGES: u32 = 10;
+const MAX_LANGUAGE_REPOS: usize = 100;
+const DEFAULT_SAMPLE_SIZE: u32 = 100;
+// Repositories whose commits are sampled when no `repos` scope is given
+const MAX_SAMPLED_REPOS: usize = 10;
+// Repositories whose author dates are looked up for anomaly analysis
+const MAX_ANALYZED_REPOS: usize = 5;
+// Repositories whose commits are looked up to attribute pushed commits
+// that carry none of the user's known email addresses
+const MAX_ATTRIBUTED_REPOS: usize = 5;
+
+/// The result of evaluating a criterion. `evidence` carries criterion-specific
+/// detail that is returned alongside the proof.
+#[derive(Debug, Clone)]
+pub struct VerificationOutcome {
+    pub meets_criteria: bool,
+    /// The measured value; only disclosed through bucketed proofs
+    pub value: u32,
+    /// Activity over the window, when requested through `histogram`
+    pub histogram: Option<ActivityHistogram>,
+    pub evidence: Option<serde_json::Value>,
+}
 
 pub struct VerificationEngine {
-    github_client: GitHubClient,
+    github: Arc<dyn GitHubDataSource>,
+    anomaly_policy: AnomalyPolicy,
 }
 
 impl VerificationEngine {
-    pub fn new() -> Self {
+    /// The engine shares the application's data source so that the HTTP
+    /// connection pool is reused across requests.
+    pub fn new(github: Arc<dyn GitHubDataSource>) -> Self {
         Self {
-            github_client: GitHubClient::new(),
+            github,
+            anomaly_policy: AnomalyPolicy::default(),
         }
     }
 
+    /// Sets how activity flagged by the anomaly heuristics is treated.
+    pub fn with_anomaly_policy(mut self, policy: AnomalyPolicy) -> Self {
+        self.anomaly_policy = policy;
+        self
+    }
+
     pub async fn verify_criteria(
         &self,
-        events: &[GitHubEvent],
+        username: &str,
         verification_type: VerificationType,
         threshold: u32,
-    ) -> Result<bool, AppError> {
-        let actual_value = match verification_type {
-            VerificationType::YearlyCommits => self.count_yearly_commits(events),
-            VerificationType::ConsecutiveDays => self.count_consecutive_days(events),
-            VerificationType::TotalStars => {
-                // Extract username from events (assuming all events are from the same user)
-                if let Some(event) = events.first() {
-                    self.github_client
-                        .count_total_stars(&event.actor.login)
-                        .await?
-                } else {
-                    0
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<VerificationOutcome, AppError> {
+        // Push-based criteria are analyzed for gaming; the report is kept to
+        // apply the downgrade policy once the value is known
+        let mut anomalies = None;
+        let mut histogram = None;
+
+        // Only event-based criteria need the events feed; star and repo counts
+        // come from the user's repositories and profile directly
+        let (actual_value, evidence) = match verification_type {
+            VerificationType::YearlyCommits => {
+                let events = self.github.fetch_user_events(username, progress).await?;
+                let author = CommitAuthor::from(&self.fetch_profile(username, progress).await?);
+                let window = options.window().resolve(Utc::now());
+                let report = self
+                    .analyze_if_enabled(username, &events, &window, progress)
+                    .await?;
+                let excluded = report
+                    .as_ref()
+                    .filter(|_| self.anomaly_policy == AnomalyPolicy::Exclude);
+                let attribution = self
+                    .attribute_commits(
+                        username,
+                        &events,
+                        &window,
+                        &options.repos,
+                        &author,
+                        progress,
+                    )
+                    .await;
+                let (commits, mut evidence, daily) = self.count_yearly_commits(
+                    &events,
+                    &window,
+                    &options.repos,
+                    &author,
+                    &attribution,
+                    excluded,
+                );
+                histogram = options.histogram.map(|granularity| {
+                    ActivityHistogram::new(
+                        granularity,
+                        window.from.date_naive(),
+                        window.to.date_naive(),
+                        &daily,
+                    )
+                });
+                if let Some(report) = &report {
+                    evidence["anomalies"] = report.to_evidence(self.anomaly_policy);
                 }
+                anomalies = report;
+                (commits, Some(evidence))
             }
-            VerificationType::PublicRepos => {
-                // Extract username from events
-                if let Some(event) = events.first() {
-                    self.github_client
-                        .count_public_repos(&event.actor.login)
-                        .await?
-                } else {
-                    0
+            VerificationType::ConsecutiveDays => {
+                let events = self.github.fetch_user_events(username, progress).await?;
+                let window = options.window().resolve(Utc::now());
+                let report = self
+                    .analyze_if_enabled(username, &events, &window, progress)
+                    .await?;
+                let excluded = report
+                    .as_ref()
+                    .filter(|_| self.anomaly_policy == AnomalyPolicy::Exclude);
+                let (days, mut evidence, daily) =
+                    self.count_consecutive_days(&events, options, excluded)?;
+                histogram = options.histogram.map(|granularity| {
+                    // Days are counted in the requested time zone, so they may
+                    // reach a day past the UTC dates of the window
+                    let (from, to) = (window.from.date_naive(), window.to.date_naive());
+                    let start = daily.keys().next().map_or(from, |&first| first.min(from));
+                    let end = daily.keys().next_back().map_or(to, |&last| last.max(to));
+                    ActivityHistogram::new(granularity, start, end, &daily)
+                });
+                if let Some(report) = &report {
+                    evidence["anomalies"] = report.to_evidence(self.anomaly_policy);
                 }
+                anomalies = report;
+                (days, Some(evidence))
+            }
+            VerificationType::TotalStars => {
+                let (stars, evidence) = self.count_total_stars(username, options, progress).await?;
+                (stars, Some(evidence))
+            }
+            VerificationType::PublicRepos => {
+                let (repos, evidence) =
+                    self.count_public_repos(username, options, progress).await?;
+                (repos, Some(evidence))
+            }
+            VerificationType::AccountAgeDays => {
+                let (days, evidence) = self.count_account_age_days(username, progress).await?;
+                (days, Some(evidence))
+            }
+            VerificationType::Followers => {
+                let (followers, evidence) = self.count_followers(username, progress).await?;
+                (followers, Some(evidence))
+            }
+            VerificationType::OrgMembership => {
+                let (is_member, evidence) = self
+                    .check_org_membership(username, options, progress)
+                    .await?;
+                (u32::from(is_member), Some(evidence))
+            }
+            VerificationType::RepoPermission => {
+                let (has_permission, evidence) = self
+                    .check_repo_permission(username, options, progress)
+                    .await?;
+                (u32::from(has_permission), Some(evidence))
+            }
+            VerificationType::MergedPullRequests => {
+                let (count, evidence) = self
+                    .count_merged_pull_requests(username, options, progress)
+                    .await?;
+                (count, Some(evidence))
+            }
+            VerificationType::OpenedIssues => {
+                let (count, evidence) = self
+                    .count_opened_issues(username, options, progress)
+                    .await?;
+                (count, Some(evidence))
+            }
+            VerificationType::RepoCommits => {
+                let (count, evidence) =
+                    self.count_repo_commits(username, options, progress).await?;
+                (count, Some(evidence))
+            }
+            VerificationType::LanguageRepos => {
+                let (count, evidence) = self
+                    .count_language_repos(username, options, progress)
+                    .await?;
+                (count, Some(evidence))
+            }
+            VerificationType::LanguageBytes => {
+                let (bytes, evidence) = self
+                    .count_language_bytes(username, options, progress)
+                    .await?;
+                (bytes, Some(evidence))
+            }
+            VerificationType::SignedCommits => {
+                let (percentage, evidence) = self
+                    .measure_signed_commits(username, options, progress)
+                    .await?;
+                (percentage, Some(evidence))
+            }
+            VerificationType::CodeReviews => {
+                let (count, evidence) =
+                    self.count_code_reviews(username, options, progress).await?;
+                (count, Some(evidence))
             }
         };
 
-        let meets_criteria = actual_value >= threshold;
+        progress.report(
+            VerificationPhase::Evaluating,
+            format!("Evaluating {:?} criterion", verification_type),
+            None,
+        );
+
+        let mut meets_criteria = actual_value >= threshold;
+        if self.anomaly_policy == AnomalyPolicy::Downgrade
+            && anomalies.as_ref().is_some_and(|report| !report.is_empty())
+        {
+            warn!(
+                "Anomalous activity found for {}, failing {:?} criterion",
+                username, verification_type
+            );
+            meets_criteria = false;
+        }
 
         info!(
             "Verification result - Type: {:?}, Threshold: {}, Actual: {}, Meets criteria: {}",
             verification_type, threshold, actual_value, meets_criteria
         );
 
-        Ok(meets_criteria)
+        Ok(VerificationOutcome {
+            meets_criteria,
+            value: actual_value,
+            histogram,
+            evidence,
+        })
     }
 
-    fn count_yearly_commits(&self, events: &[GitHubEvent]) -> u32 {
-        let one_year_ago = Utc::now() - Duration::days(365);
+    async fn count_merged_pull_requests(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let window = options.window().resolve(Utc::now());
+
+        let mut merged = 0;
+        let mut by_organization: BTreeMap<String, u32> = BTreeMap::new();
+        let mut truncated = false;
 
-        // Debug: Count all events first
-        let total_events = events.len();
-        let push_events: Vec<_> = events
+        for page in 1..=MAX_SEARCH_PAGES {
+            let results = self
+                .github
+                .search_merged_pull_requests(
+                    username,
+                    window.from,
+                    window.to,
+                    options.exclude_own_repos,
+                    &options.repos,
+                    page,
+                )
+                .await?;
+            if results.items.is_empty() {
+                break;
+            }
+
+            for pull_request in &results.items {
+                // The search qualifiers are only a pre-filter; re-check the
+                // window and repository owner on every result
+                if !pull_request
+                    .merged_at()
+                    .is_some_and(|merged_at| window.contains(merged_at))
+                {
+                    continue;
+                }
+                let owner = pull_request.repository_owner();
+                if options.exclude_own_repos && owner.eq_ignore_ascii_case(username) {
+                    continue;
+                }
+                if !in_scope(&options.repos, pull_request.repository_full_name()) {
+                    continue;
+                }
+
+                merged += 1;
+                *by_organization.entry(owner.to_string()).or_insert(0) += 1;
+            }
+
+            progress.report(
+                VerificationPhase::FetchingPullRequests,
+                format!("Fetched merged pull requests page {}", page),
+                Some(merged),
+            );
+
+            if page * SEARCH_PAGE_SIZE >= results.total_count {
+                break;
+            }
+            if page == MAX_SEARCH_PAGES {
+                warn!(
+                    "User {} has more than {} merged pull requests, limiting count",
+                    username,
+                    MAX_SEARCH_PAGES * SEARCH_PAGE_SIZE
+                );
+                truncated = true;
+            }
+        }
+
+        info!(
+            "User {} has {} merged pull requests between {} and {} across {} owners",
+            username,
+            merged,
+            window.from,
+            window.to,
+            by_organization.len()
+        );
+
+        let evidence = json!({
+            "window": window,
+            "exclude_own_repos": options.exclude_own_repos,
+            "merged_pull_requests": merged,
+            "by_organization": by_organization,
+            "truncated": truncated,
+        });
+        Ok((merged, evidence))
+    }
+
+    async fn count_opened_issues(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let window = options.window().resolve(Utc::now());
+
+        let mut opened = 0;
+        let mut by_organization: BTreeMap<String, u32> = BTreeMap::new();
+        let mut truncated = false;
+
+        for page in 1..=MAX_SEARCH_PAGES {
+            let results = self
+                .github
+                .search_opened_issues(
+                    username,
+                    window.from,
+                    window.to,
+                    options.exclude_own_repos,
+                    &options.repos,
+                    page,
+                )
+                .await?;
+            if results.items.is_empty() {
+                break;
+            }
+
+            for issue in &results.items {
+                // Same as for pull requests, the search is only a pre-filter
+                if issue.pull_request.is_some() || !window.contains(issue.created_at) {
+                    continue;
+                }
+                let owner = issue.repository_owner();
+                if options.exclude_own_repos && owner.eq_ignore_ascii_case(username) {
+                    continue;
+                }
+                if !in_scope(&options.repos, issue.repository_full_name()) {
+                    continue;
+                }
+
+                opened += 1;
+                *by_organization.entry(owner.to_string()).or_insert(0) += 1;
+            }
+
+            progress.report(
+                VerificationPhase::FetchingIssues,
+                format!("Fetched opened issues page {}", page),
+                Some(opened),
+            );
+
+            if page * SEARCH_PAGE_SIZE >= results.total_count {
+                break;
+            }
+            if page == MAX_SEARCH_PAGES {
+                warn!(
+                    "User {} has opened more than {} issues, limiting count",
+                    username,
+                    MAX_SEARCH_PAGES * SEARCH_PAGE_SIZE
+                );
+                truncated = true;
+            }
+        }
+
+        info!(
+            "User {} opened {} issues between {} and {} across {} owners",
+            username,
+            opened,
+            window.from,
+            window.to,
+            by_organization.len()
+        );
+
+        let evidence = json!({
+            "window": window,
+            "exclude_own_repos": options.exclude_own_repos,
+            "opened_issues": opened,
+            "by_organization": by_organization,
+            "truncated": truncated,
+        });
+        Ok((opened, evidence))
+    }
+
+    async fn count_repo_commits(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        if options.repos.is_empty() {
+            return Err(AppError::Validation(
+                "repo_commits requires at least one repository in 'repos'".to_string(),
+            ));
+        }
+
+        let window = options.window().resolve(Utc::now());
+
+        // A commit reachable from several of the given repositories (e.g. a
+        // fork and its upstream) is only counted once
+        let mut seen = HashSet::new();
+        let mut by_repository: BTreeMap<String, u32> = BTreeMap::new();
+        let mut truncated = false;
+
+        for repo in &options.repos {
+            let mut repo_commits = 0;
+
+            for page in 1..=MAX_COMMIT_PAGES {
+                let commits = self
+                    .github
+                    .fetch_repo_commits(repo, username, window.from, window.to, page)
+                    .await?;
+                let page_len = commits.len() as u32;
+
+                for commit in commits {
+                    if !commit
+                        .authored_at()
+                        .is_some_and(|authored_at| window.contains(authored_at))
+                    {
+                        continue;
+                    }
+                    if seen.insert(commit.sha) {
+                        repo_commits += 1;
+                    }
+                }
+
+                progress.report(
+                    VerificationPhase::FetchingCommits,
+                    format!("Fetched commits to {} (page {})", repo, page),
+                    Some(seen.len() as u32),
+                );
+
+                if page_len < COMMITS_PER_PAGE {
+                    break;
+                }
+                if page == MAX_COMMIT_PAGES {
+                    warn!(
+                        "User {} has more than {} commits to {}, limiting count",
+                        username,
+                        MAX_COMMIT_PAGES * COMMITS_PER_PAGE,
+                        repo
+                    );
+                    truncated = true;
+                }
+            }
+
+            by_repository.insert(repo.clone(), repo_commits);
+        }
+
+        let commits = seen.len() as u32;
+        info!(
+            "User {} authored {} commits to {} repositories between {} and {}",
+            username,
+            commits,
+            options.repos.len(),
+            window.from,
+            window.to
+        );
+
+        let evidence = json!({
+            "window": window,
+            "repositories": options.repos,
+            "commits": commits,
+            "by_repository": by_repository,
+            "truncated": truncated,
+        });
+        Ok((commits, evidence))
+    }
+
+    /// Percentage of the user's most recent commits whose signature GitHub
+    /// verified. Commits are sampled from `repos`, or else from the user's
+    /// most recently pushed non-fork repositories.
+    async fn measure_signed_commits(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let window = options.window().resolve(Utc::now());
+        let sample_size = options.sample_size.unwrap_or(DEFAULT_SAMPLE_SIZE) as usize;
+
+        let repos = if options.repos.is_empty() {
+            let mut repos: Vec<GitHubUserRepo> = self
+                .fetch_all_repos(username, progress)
+                .await?
+                .into_iter()
+                .filter(|repo| {
+                    !repo.fork && repo.pushed_at.is_some_and(|pushed| pushed >= window.from)
+                })
+                .collect();
+            repos.sort_by_key(|repo| std::cmp::Reverse(repo.pushed_at));
+            repos
+                .into_iter()
+                .take(MAX_SAMPLED_REPOS)
+                .map(|repo| format!("{}/{}", username, repo.name))
+                .collect()
+        } else {
+            options.repos.clone()
+        };
+
+        let mut seen = HashSet::new();
+        let mut commits = Vec::new();
+        for repo in &repos {
+            // The newest `sample_size` commits of every repository are enough
+            // to pick the newest `sample_size` overall
+            let pages = (sample_size as u32).div_ceil(COMMITS_PER_PAGE);
+            for page in 1..=pages {
+                let page_commits = self
+                    .github
+                    .fetch_repo_commits(repo, username, window.from, window.to, page)
+                    .await?;
+                let page_len = page_commits.len() as u32;
+
+                commits.extend(page_commits.into_iter().filter(|commit| {
+                    commit
+                        .authored_at()
+                        .is_some_and(|authored_at| window.contains(authored_at))
+                        && seen.insert(commit.sha.clone())
+                }));
+
+                progress.report(
+                    VerificationPhase::FetchingCommits,
+                    format!("Sampled commits from {} (page {})", repo, page),
+                    Some(commits.len() as u32),
+                );
+
+                if page_len < COMMITS_PER_PAGE {
+                    break;
+                }
+            }
+        }
+
+        commits.sort_by_key(|commit| std::cmp::Reverse(commit.authored_at()));
+        commits.truncate(sample_size);
+
+        let verified = commits.iter().filter(|commit| commit.is_verified()).count();
+        let percentage = if commits.is_empty() {
+            0
+        } else {
+            (verified * 100 / commits.len()) as u32
+        };
+
+        info!(
+            "User {} signed {} of {} sampled commits ({}%) between {} and {}",
+            username,
+            verified,
+            commits.len(),
+            percentage,
+            window.from,
+            window.to
+        );
+
+        let evidence = json!({
+            "window": window,
+            "sample_size": commits.len(),
+            "verified_commits": verified,
+            "verified_percentage": percentage,
+            "repositories_sampled": repos.len(),
+        });
+        Ok((percentage, evidence))
+    }
+
+    async fn count_code_reviews(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let window = options.window().resolve(Utc::now());
+
+        progress.report(
+            VerificationPhase::FetchingReviews,
+            format!("Fetching pull request reviews for {}", username),
+            None,
+        );
+
+        // The GraphQL contributions API covers the whole window but needs a
+        // token; without one, fall back to the (90 day) public events feed
+        let (source, contributions) = match self
+            .github
+            .fetch_review_contributions(username, window.from, window.to)
+            .await
+        {
+            Ok(contributions) => ("graphql", contributions),
+            Err(GitHubError::TokenRequired(_)) => {
+                let events = self.github.fetch_user_events(username, progress).await?;
+                let contributions = GitHubReviewContributions {
+                    reviews: review_contributions_from_events(&events),
+                    truncated: false,
+                };
+                ("events", contributions)
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut reviewed = 0;
+        let mut by_state: BTreeMap<String, u32> = BTreeMap::new();
+
+        for review in &contributions.reviews {
+            if !window.contains(review.occurred_at) {
+                continue;
+            }
+            let state = review.state.to_ascii_uppercase();
+            if options.substantive_reviews_only
+                && state != "APPROVED"
+                && state != "CHANGES_REQUESTED"
+            {
+                continue;
+            }
+            let owner = review.repository.split('/').next().unwrap_or_default();
+            if options.exclude_own_repos && owner.eq_ignore_ascii_case(username) {
+                continue;
+            }
+            if !in_scope(&options.repos, &review.repository) {
+                continue;
+            }
+
+            reviewed += 1;
+            *by_state.entry(state).or_insert(0) += 1;
+        }
+
+        progress.report(
+            VerificationPhase::FetchingReviews,
+            "Counted pull request reviews",
+            Some(reviewed),
+        );
+
+        info!(
+            "User {} submitted {} reviews between {} and {} (source: {})",
+            username, reviewed, window.from, window.to, source
+        );
+
+        let evidence = json!({
+            "window": window,
+            "source": source,
+            "exclude_own_repos": options.exclude_own_repos,
+            "substantive_reviews_only": options.substantive_reviews_only,
+            "code_reviews": reviewed,
+            "by_state": by_state,
+            "truncated": contributions.truncated,
+        });
+        Ok((reviewed, evidence))
+    }
+
+    /// All of the user's public repositories, up to 10 pages (1000 repos).
+    async fn fetch_all_repos(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<GitHubUserRepo>, AppError> {
+        let mut all_repos = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let repos = self.github.fetch_user_repos(username, page).await?;
+            if repos.is_empty() {
+                break;
+            }
+
+            all_repos.extend(repos);
+            progress.report(
+                VerificationPhase::FetchingRepos,
+                format!("Fetched repositories page {}", page),
+                Some(all_repos.len() as u32),
+            );
+            page += 1;
+
+            // Limit to 10 pages (1000 repos) to prevent excessive API calls
+            if page > 10 {
+                warn!(
+                    "User {} has more than 1000 repos, limiting to the first 1000",
+                    username
+                );
+                break;
+            }
+        }
+
+        debug!(
+            "Fetched {} repos for {} across {} pages",
+            all_repos.len(),
+            username,
+            page - 1
+        );
+        Ok(all_repos)
+    }
+
+    /// Applies the repository filters from `options`, returning the kept
+    /// repositories and how many were excluded for each reason. A repository
+    /// is only counted under the first reason that excludes it.
+    async fn filter_repos(
+        &self,
+        username: &str,
+        repos: Vec<GitHubUserRepo>,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(Vec<GitHubUserRepo>, serde_json::Value), AppError> {
+        let created_before = options
+            .min_repo_age_days
+            .map(|days| Utc::now() - Duration::days(days as i64));
+
+        let mut kept = Vec::new();
+        let mut forks = 0;
+        let mut archived = 0;
+        let mut templates = 0;
+        let mut recently_created = 0;
+        let mut without_commits = 0;
+
+        for repo in repos {
+            if options.exclude_forks == Some(true) && repo.fork {
+                forks += 1;
+            } else if options.exclude_archived && repo.archived {
+                archived += 1;
+            } else if options.exclude_templates && repo.is_template {
+                templates += 1;
+            } else if created_before.is_some_and(|before| repo.created_at > before) {
+                recently_created += 1;
+            } else if options.exclude_repos_without_commits
+                && !self.has_commits_by(username, &repo, progress).await?
+            {
+                without_commits += 1;
+            } else {
+                kept.push(repo);
+            }
+        }
+
+        let excluded = json!({
+            "forks": forks,
+            "archived": archived,
+            "templates": templates,
+            "recently_created": recently_created,
+            "without_commits": without_commits,
+        });
+        Ok((kept, excluded))
+    }
+
+    async fn has_commits_by(
+        &self,
+        username: &str,
+        repo: &GitHubUserRepo,
+        progress: &ProgressReporter,
+    ) -> Result<bool, AppError> {
+        let full_name = format!("{}/{}", username, repo.name);
+        progress.report(
+            VerificationPhase::FetchingCommits,
+            format!("Checking commits to {}", full_name),
+            None,
+        );
+        let commits = self
+            .github
+            .fetch_repo_commits(&full_name, username, DateTime::UNIX_EPOCH, Utc::now(), 1)
+            .await?;
+        Ok(!commits.is_empty())
+    }
+
+    async fn count_total_stars(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let repos = self.fetch_all_repos(username, progress).await?;
+        let repositories_total = repos.len();
+        let (repos, excluded) = self
+            .filter_repos(username, repos, options, progress)
+            .await?;
+        let total_stars = repos.iter().map(|repo| repo.stargazers_count).sum::<u32>();
+
+        info!(
+            "User {} has {} total stars across {} of {} repos",
+            username,
+            total_stars,
+            repos.len(),
+            repositories_total
+        );
+
+        let evidence = json!({
+            "total_stars": total_stars,
+            "repositories_total": repositories_total,
+            "repositories_counted": repos.len(),
+            "excluded": excluded,
+        });
+        Ok((total_stars, evidence))
+    }
+
+    async fn count_language_repos(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let language = required_language(options)?;
+        let exclude_forks = options.exclude_forks.unwrap_or(true);
+
+        let repos = self.fetch_all_repos(username, progress).await?;
+        let scanned = repos
             .iter()
-            .filter(|event| event.event_type == "PushEvent")
-            .collect();
-        let recent_push_events: Vec<_> = events
+            .filter(|repo| !(exclude_forks && repo.fork))
+            .count();
+        let matching = repos
             .iter()
-            .filter(|event| event.event_type == "PushEvent" && event.created_at >= one_year_ago)
+            .filter(|repo| !(exclude_forks && repo.fork))
+            .filter(|repo| {
+                repo.language
+                    .as_deref()
+                    .is_some_and(|primary| primary.eq_ignore_ascii_case(language))
+            })
+            .count() as u32;
+
+        info!(
+            "User {} has {} of {} repos primarily in {}",
+            username, matching, scanned, language
+        );
+
+        let evidence = json!({
+            "language": language,
+            "exclude_forks": exclude_forks,
+            "repositories_scanned": scanned,
+            "language_repos": matching,
+        });
+        Ok((matching, evidence))
+    }
+
+    async fn count_language_bytes(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let language = required_language(options)?;
+        let exclude_forks = options.exclude_forks.unwrap_or(true);
+
+        let mut repos: Vec<GitHubUserRepo> = self
+            .fetch_all_repos(username, progress)
+            .await?
+            .into_iter()
+            .filter(|repo| !(exclude_forks && repo.fork))
             .collect();
 
-        debug!(
-            "Total events: {}, Push events: {}, Recent push events: {}",
-            total_events,
-            push_events.len(),
-            recent_push_events.len()
+        // One request per repository, so only the most recently pushed ones
+        // are scanned
+        repos.sort_by_key(|repo| std::cmp::Reverse(repo.pushed_at));
+        let truncated = repos.len() > MAX_LANGUAGE_REPOS;
+        repos.truncate(MAX_LANGUAGE_REPOS);
+
+        let mut total_bytes: u64 = 0;
+        for (i, repo) in repos.iter().enumerate() {
+            let full_name = format!("{}/{}", username, repo.name);
+            let languages = self
+                .github
+                .fetch_repo_languages(&full_name, username)
+                .await?;
+            total_bytes += languages
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(language))
+                .map(|(_, bytes)| bytes)
+                .sum::<u64>();
+
+            progress.report(
+                VerificationPhase::FetchingLanguages,
+                format!("Fetched languages of {}", full_name),
+                Some(i as u32 + 1),
+            );
+        }
+
+        info!(
+            "User {} has {} bytes of {} across {} repos",
+            username,
+            total_bytes,
+            language,
+            repos.len()
+        );
+
+        let evidence = json!({
+            "language": language,
+            "exclude_forks": exclude_forks,
+            "repositories_scanned": repos.len(),
+            "language_bytes": total_bytes,
+            "truncated": truncated,
+        });
+        Ok((u32::try_from(total_bytes).unwrap_or(u32::MAX), evidence))
+    }
+
+    async fn fetch_profile(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<GitHubUser, AppError> {
+        progress.report(
+            VerificationPhase::FetchingUser,
+            format!("Fetching profile for {}", username),
+            None,
+        );
+        Ok(self.github.fetch_user(username).await?)
+    }
+
+    async fn count_public_repos(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        // Without filters the profile's counter is enough; filtering needs the
+        // repositories themselves
+        if !options.has_repo_filters() {
+            let user = self.fetch_profile(username, progress).await?;
+            info!("User {} has {} public repos", username, user.public_repos);
+            let evidence = json!({
+                "public_repos": user.public_repos,
+            });
+            return Ok((user.public_repos, evidence));
+        }
+
+        let repos = self.fetch_all_repos(username, progress).await?;
+        let repositories_total = repos.len();
+        let (repos, excluded) = self
+            .filter_repos(username, repos, options, progress)
+            .await?;
+        let public_repos = repos.len() as u32;
+        info!(
+            "User {} has {} public repos after filtering {}",
+            username, public_repos, repositories_total
+        );
+
+        let evidence = json!({
+            "public_repos": public_repos,
+            "repositories_total": repositories_total,
+            "excluded": excluded,
+        });
+        Ok((public_repos, evidence))
+    }
+
+    async fn count_account_age_days(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let user = self.fetch_profile(username, progress).await?;
+        let age_days = (Utc::now() - user.created_at).num_days().max(0) as u32;
+        info!(
+            "User {} account is {} days old (created {})",
+            username, age_days, user.created_at
         );
 
-        let mut total_commits = 0;
-        for (i, event) in recent_push_events.iter().enumerate() {
-            let commits_in_event = event
+        let evidence = json!({
+            "created_at": user.created_at,
+            "account_age_days": age_days,
+        });
+        Ok((age_days, evidence))
+    }
+
+    async fn count_followers(
+        &self,
+        username: &str,
+        progress: &ProgressReporter,
+    ) -> Result<(u32, serde_json::Value), AppError> {
+        let user = self.fetch_profile(username, progress).await?;
+        info!("User {} has {} followers", username, user.followers);
+
+        let evidence = json!({ "followers": user.followers });
+        Ok((user.followers, evidence))
+    }
+
+    async fn check_org_membership(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(bool, serde_json::Value), AppError> {
+        let org = options.org.as_deref().ok_or_else(|| {
+            AppError::Validation("org_membership requires the 'org' option".to_string())
+        })?;
+
+        progress.report(
+            VerificationPhase::CheckingAccess,
+            format!("Checking public membership in {}", org),
+            None,
+        );
+        let is_member = self.github.check_public_membership(org, username).await?;
+        info!("User {} public member of {}: {}", username, org, is_member);
+
+        // Only the fact itself is attested; nothing else about the membership
+        let evidence = json!({
+            "organization": org,
+            "public_member": is_member,
+        });
+        Ok((is_member, evidence))
+    }
+
+    async fn check_repo_permission(
+        &self,
+        username: &str,
+        options: &CriteriaOptions,
+        progress: &ProgressReporter,
+    ) -> Result<(bool, serde_json::Value), AppError> {
+        let repo = options.repo.as_deref().ok_or_else(|| {
+            AppError::Validation("repo_permission requires the 'repo' option".to_string())
+        })?;
+        let required = options.permission.unwrap_or_default();
+
+        progress.report(
+            VerificationPhase::CheckingAccess,
+            format!("Checking permission on {}", repo),
+            None,
+        );
+        let permission = self
+            .github
+            .fetch_collaborator_permission(repo, username)
+            .await?;
+        let has_permission = permission.rank() >= required.rank();
+        info!(
+            "User {} has {:?} permission on {}: {}",
+            username, required, repo, has_permission
+        );
+
+        let evidence = json!({
+            "repository": repo,
+            "required_permission": required,
+            "has_permission": has_permission,
+        });
+        Ok((has_permission, evidence))
+    }
+
+    /// Analyzes the activity unless the policy is `off`. Only the criteria
+    /// counting pushed commits call this, so other criteria never pay for
+    /// the extra GitHub requests.
+    async fn analyze_if_enabled(
+        &self,
+        username: &str,
+        events: &[GitHubEvent],
+        window: &ResolvedWindow,
+        progress: &ProgressReporter,
+    ) -> Result<Option<AnomalyReport>, AppError> {
+        if !self.anomaly_policy.is_enabled() {
+            return Ok(None);
+        }
+        self.analyze_activity(username, events, window, progress)
+            .await
+            .map(Some)
+    }
+
+    /// Runs the anomaly heuristics over the commits pushed within the window.
+    /// Author dates and repository metadata only sharpen the analysis, so
+    /// failures to fetch them are logged rather than failing verification.
+    async fn analyze_activity(
+        &self,
+        username: &str,
+        events: &[GitHubEvent],
+        window: &ResolvedWindow,
+        progress: &ProgressReporter,
+    ) -> Result<AnomalyReport, AppError> {
+        let mut commits: Vec<ActivityCommit> = events
+            .iter()
+            .filter(|event| event.event_type == "PushEvent" && window.contains(event.created_at))
+            .flat_map(|event| {
+                event
+                    .payload
+                    .get("commits")
+                    .and_then(|commits| commits.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|commit| {
+                        Some(ActivityCommit {
+                            sha: commit.get("sha")?.as_str()?.to_string(),
+                            repository: event.repo.name.clone(),
+                            message: commit
+                                .get("message")
+                                .and_then(|message| message.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                            pushed_at: event.created_at,
+                            authored_at: None,
+                        })
+                    })
+            })
+            .collect();
+
+        // Author dates of the most active repositories; the lookup isn't
+        // bounded by the window so that commits backdated before it show up
+        let mut commits_per_repo: HashMap<&str, usize> = HashMap::new();
+        for commit in &commits {
+            *commits_per_repo
+                .entry(commit.repository.as_str())
+                .or_insert(0) += 1;
+        }
+        let mut analyzed_repos: Vec<(&str, usize)> = commits_per_repo.into_iter().collect();
+        analyzed_repos.sort_by_key(|&(repo, count)| (std::cmp::Reverse(count), repo));
+
+        let mut authored_at = HashMap::new();
+        for (repo, _) in analyzed_repos.into_iter().take(MAX_ANALYZED_REPOS) {
+            match self
+                .github
+                .fetch_repo_commits(repo, username, DateTime::UNIX_EPOCH, window.to, 1)
+                .await
+            {
+                Ok(repo_commits) => authored_at.extend(
+                    repo_commits
+                        .iter()
+                        .filter_map(|commit| Some((commit.sha.clone(), commit.authored_at()?))),
+                ),
+                Err(err) => warn!("Skipping author dates of {}: {}", repo, err),
+            }
+        }
+        for commit in &mut commits {
+            commit.authored_at = authored_at.get(&commit.sha).copied();
+        }
+
+        let repos: HashMap<String, GitHubUserRepo> =
+            match self.fetch_all_repos(username, progress).await {
+                Ok(repos) => repos
+                    .into_iter()
+                    .map(|repo| {
+                        (
+                            format!("{}/{}", username, repo.name).to_ascii_lowercase(),
+                            repo,
+                        )
+                    })
+                    .collect(),
+                Err(err) => {
+                    warn!("Skipping repository metadata for {}: {}", username, err);
+                    HashMap::new()
+                }
+            };
+
+        let report = anomaly::analyze(&commits, &repos, window.from);
+        if !report.is_empty() {
+            info!(
+                "Found {} activity anomalies for {}",
+                report.anomalies.len(),
+                username
+            );
+        }
+        Ok(report)
+    }
+
+    /// Looks up which pushed commits GitHub attributes to the user when their
+    /// author email isn't one `CommitAuthor` recognizes, such as a verified
+    /// private address. The commits API's author filter resolves every
+    /// verified email of the account. Only the repositories with the most
+    /// such commits are looked up; the others, and those whose lookup fails,
+    /// are reported as unchecked.
+    async fn attribute_commits(
+        &self,
+        username: &str,
+        events: &[GitHubEvent],
+        window: &ResolvedWindow,
+        repos: &[String],
+        author: &CommitAuthor,
+        progress: &ProgressReporter,
+    ) -> Attribution {
+        let mut candidates: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for event in events.iter().filter(|event| {
+            event.event_type == "PushEvent"
+                && window.contains(event.created_at)
+                && in_scope(repos, &event.repo.name)
+        }) {
+            let commits = event
+                .payload
+                .get("commits")
+                .and_then(|commits| commits.as_array())
+                .into_iter()
+                .flatten();
+            for commit in commits {
+                if commit
+                    .get("author")
+                    .is_some_and(|commit_author| author.matches(commit_author))
+                {
+                    continue;
+                }
+                if let Some(sha) = commit.get("sha").and_then(|sha| sha.as_str()) {
+                    candidates
+                        .entry(event.repo.name.as_str())
+                        .or_default()
+                        .insert(sha);
+                }
+            }
+        }
+
+        let mut by_count: Vec<(&str, HashSet<&str>)> = candidates.into_iter().collect();
+        by_count.sort_by_key(|(repo, shas)| (std::cmp::Reverse(shas.len()), *repo));
+
+        let mut attribution = Attribution::default();
+        for (index, (repo, mut unresolved)) in by_count.into_iter().enumerate() {
+            if index >= MAX_ATTRIBUTED_REPOS {
+                attribution.unchecked_repos.insert(repo.to_string());
+                continue;
+            }
+            progress.report(
+                VerificationPhase::FetchingCommits,
+                format!("Attributing commits to {}", repo),
+                None,
+            );
+
+            // Pushed commits may have been made before the window started
+            for page in 1..=MAX_COMMIT_PAGES {
+                let commits = match self
+                    .github
+                    .fetch_repo_commits(repo, username, DateTime::UNIX_EPOCH, window.to, page)
+                    .await
+                {
+                    Ok(commits) => commits,
+                    Err(err) => {
+                        warn!("Skipping commit attribution in {}: {}", repo, err);
+                        attribution.unchecked_repos.insert(repo.to_string());
+                        break;
+                    }
+                };
+                let page_len = commits.len() as u32;
+                for commit in commits {
+                    if unresolved.remove(commit.sha.as_str()) {
+                        attribution.shas.insert(commit.sha);
+                    }
+                }
+                if unresolved.is_empty() || page_len < COMMITS_PER_PAGE {
+                    break;
+                }
+            }
+        }
+        attribution
+    }
+
+    /// Counts commits pushed within the window to the `repos` in scope, each
+    /// SHA once. Commits that GitHub marks as not distinct (already in the
+    /// repository, e.g. after a force-push or a branch copy) or that were
+    /// authored by someone else are excluded. Commits that neither carry one
+    /// of the user's known emails nor were looked up in `attribution` are
+    /// reported as unattributed. Push payloads list at most 20 commits; the
+    /// remaining ones can't be attributed or deduplicated and are excluded as
+    /// well, as are commits flagged in `excluded`.
+    fn count_yearly_commits(
+        &self,
+        events: &[GitHubEvent],
+        window: &ResolvedWindow,
+        repos: &[String],
+        author: &CommitAuthor,
+        attribution: &Attribution,
+        excluded: Option<&AnomalyReport>,
+    ) -> (u32, serde_json::Value, DailyCounts) {
+        let push_events: Vec<_> = events
+            .iter()
+            .filter(|event| event.event_type == "PushEvent" && window.contains(event.created_at))
+            .filter(|event| in_scope(repos, &event.repo.name))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut duplicates = 0;
+        let mut not_distinct = 0;
+        let mut other_authors = 0;
+        let mut unattributed = 0;
+        let mut unlisted = 0;
+        let mut anomalous = 0;
+        let mut daily = DailyCounts::new();
+
+        for event in &push_events {
+            let commits = event
                 .payload
                 .get("commits")
                 .and_then(|commits| commits.as_array())
-                .map(|commits| commits.len() as u32)
-                .unwrap_or(0);
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            let mut listed_distinct = 0;
+            for commit in commits {
+                // `distinct` is absent from older payloads; treat it as true
+                let distinct = commit
+                    .get("distinct")
+                    .and_then(|distinct| distinct.as_bool())
+                    .unwrap_or(true);
+                if !distinct {
+                    not_distinct += 1;
+                    continue;
+                }
+                listed_distinct += 1;
+
+                let Some(sha) = commit.get("sha").and_then(|sha| sha.as_str()) else {
+                    continue;
+                };
+                if !commit
+                    .get("author")
+                    .is_some_and(|commit_author| author.matches(commit_author))
+                    && !attribution.shas.contains(sha)
+                {
+                    if attribution.unchecked_repos.contains(&event.repo.name) {
+                        unattributed += 1;
+                    } else {
+                        other_authors += 1;
+                    }
+                    continue;
+                }
+                if excluded.is_some_and(|report| report.is_flagged(sha)) {
+                    anomalous += 1;
+                    continue;
+                }
+                if seen.insert(sha.to_string()) {
+                    *daily.entry(event.created_at.date_naive()).or_insert(0) += 1;
+                } else {
+                    duplicates += 1;
+                }
+            }
+
+            let distinct_size = event
+                .payload
+                .get("distinct_size")
+                .and_then(|size| size.as_u64())
+                .unwrap_or(listed_distinct);
+            unlisted += distinct_size.saturating_sub(listed_distinct);
 
             debug!(
-                "Push event {}: {} commits at {}",
-                i + 1,
-                commits_in_event,
+                "Push event {}: {} commits listed, {} distinct at {}",
+                event.id,
+                commits.len(),
+                distinct_size,
                 event.created_at
             );
-            total_commits += commits_in_event;
         }
 
-        info!("COMMIT COUNT BREAKDOWN - Total events: {}, Push events: {}, Recent push events: {}, Total commits: {}", 
-              total_events, push_events.len(), recent_push_events.len(), total_commits);
+        let total_commits = seen.len() as u32;
+        info!(
+            "COMMIT COUNT BREAKDOWN - Total events: {}, Push events: {}, Distinct commits: {}, Excluded: {} duplicate, {} not distinct, {} other authors, {} unattributed, {} unlisted, {} anomalous",
+            events.len(),
+            push_events.len(),
+            total_commits,
+            duplicates,
+            not_distinct,
+            other_authors,
+            unattributed,
+            unlisted,
+            anomalous
+        );
 
-        total_commits
+        let evidence = json!({
+            "window": window,
+            "commits": total_commits,
+            "excluded": {
+                "duplicates": duplicates,
+                "not_distinct": not_distinct,
+                "other_authors": other_authors,
+                "unattributed": unattributed,
+                "unlisted": unlisted,
+                "anomalous": anomalous,
+            },
+        });
+        (total_commits, evidence, daily)
     }
 
-    fn count_consecutive_days(&self, events: &[GitHubEvent]) -> u32 {
-        // Collect all unique activity dates
-        let mut activity_dates = HashSet::new();
+    /// Only events in the `repos` in scope count as activity. Push events
+    /// whose commits were all flagged in `excluded` don't count either.
+    fn count_consecutive_days(
+        &self,
+        events: &[GitHubEvent],
+        options: &CriteriaOptions,
+        excluded: Option<&AnomalyReport>,
+    ) -> Result<(u32, serde_json::Value, DailyCounts), AppError> {
+        let time_zone = match &options.time_zone {
+            Some(time_zone) => validation::parse_time_zone(time_zone)?,
+            None => Tz::UTC,
+        };
+        let streak = options.streak.unwrap_or_default();
+        let window = options.window().resolve(Utc::now());
 
-        for event in events {
-            let date = event.created_at.date_naive();
-            activity_dates.insert(date);
+        // Count activity per date in the requested time zone
+        let mut daily = DailyCounts::new();
+        for date in events
+            .iter()
+            .filter(|event| window.contains(event.created_at))
+            .filter(|event| in_scope(&options.repos, &event.repo.name))
+            .filter(|event| {
+                !options.contribution_events_only
+                    || CONTRIBUTION_EVENT_TYPES.contains(&event.event_type.as_str())
+            })
+            .filter(|event| !excluded.is_some_and(|report| is_flagged_push(event, report)))
+            .map(|event| event.created_at.with_timezone(&time_zone).date_naive())
+        {
+            *daily.entry(date).or_insert(0) += 1;
         }
+        let activity_dates: BTreeSet<NaiveDate> = daily.keys().copied().collect();
+
+        let (days, streak_end) = match streak {
+            StreakMode::Longest => longest_streak(&activity_dates),
+            StreakMode::Current => {
+                let today = Utc::now().with_timezone(&time_zone).date_naive();
+                current_streak(&activity_dates, today)
+            }
+        };
+
+        debug!(
+            "Found {} consecutive days of activity ({:?} streak, {})",
+            days, streak, time_zone
+        );
+
+        let evidence = json!({
+            "window": window,
+            "streak": streak,
+            "time_zone": time_zone.name(),
+            "contribution_events_only": options.contribution_events_only,
+            "consecutive_days": days,
+            "streak_end": streak_end,
+        });
+        Ok((days, evidence, daily))
+    }
+}
+
+fn is_flagged_push(event: &GitHubEvent, report: &AnomalyReport) -> bool {
+    let shas: Vec<&str> = event
+        .payload
+        .get("commits")
+        .and_then(|commits| commits.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|commit| commit.get("sha")?.as_str())
+        .collect();
+    event.event_type == "PushEvent"
+        && !shas.is_empty()
+        && shas.iter().all(|sha| report.is_flagged(sha))
+}
 
-        if activity_dates.is_empty() {
-            return 0;
+fn required_language(options: &CriteriaOptions) -> Result<&str, AppError> {
+    options.language.as_deref().ok_or_else(|| {
+        AppError::Validation("Language criteria require the 'language' option".to_string())
+    })
+}
+
+/// Pushed commits that GitHub attributes to the user through the commits
+/// API, and repositories whose commits couldn't be looked up.
+#[derive(Debug, Default)]
+struct Attribution {
+    shas: HashSet<String>,
+    unchecked_repos: HashSet<String>,
+}
+
+/// Identifies the user's own commits in push payloads, whose authors only
+/// carry a name and an email address. Both are free text set by whoever made
+/// the commit, so only email addresses tied to the account count: GitHub's
+/// noreply addresses for it and its public email, which GitHub only shows
+/// once verified. Names are never matched. Commits made with the user's
+/// private verified emails are attributed through `Attribution` instead.
+struct CommitAuthor {
+    login: String,
+    id: u64,
+    email: Option<String>,
+}
+
+impl From<&GitHubUser> for CommitAuthor {
+    fn from(user: &GitHubUser) -> Self {
+        Self {
+            login: user.login.to_ascii_lowercase(),
+            id: user.id,
+            email: user.email.as_ref().map(|email| email.to_ascii_lowercase()),
         }
+    }
+}
 
-        // Sort dates
-        let mut sorted_dates: Vec<NaiveDate> = activity_dates.into_iter().collect();
-        sorted_dates.sort();
+impl CommitAuthor {
+    fn matches(&self, author: &serde_json::Value) -> bool {
+        let email = author
+            .get("email")
+            .and_then(|email| email.as_str())
+            .map(str::to_ascii_lowercase);
 
-        // Find longest consecutive streak
-        let mut max_consecutive = 1;
-        let mut current_consecutive = 1;
+        email.as_deref().is_some_and(|email| {
+            // GitHub's noreply addresses: `login@` or `id+login@`
+            let noreply = email
+                .strip_suffix("@users.noreply.github.com")
+                .is_some_and(|local| {
+                    local == self.login || local == format!("{}+{}", self.id, self.login)
+                });
+            noreply || self.email.as_deref() == Some(email)
+        })
+    }
+}
 
-        for i in 1..sorted_dates.len() {
-            let prev_date = sorted_dates[i - 1];
-            let current_date = sorted_dates[i];
+/// The longest run of consecutive dates and the date it ended on.
+fn longest_streak(dates: &BTreeSet<NaiveDate>) -> (u32, Option<NaiveDate>) {
+    let mut longest = (0, None);
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
 
-            if current_date == prev_date + Duration::days(1) {
-                current_consecutive += 1;
-            } else {
-                max_consecutive = max_consecutive.max(current_consecutive);
-                current_consecutive = 1;
-            }
+    for &date in dates {
+        current = match previous {
+            Some(prev) if date == prev + Duration::days(1) => current + 1,
+            _ => 1,
+        };
+        if current > longest.0 {
+            longest = (current, Some(date));
         }
+        previous = Some(date);
+    }
+
+    longest
+}
 
-        max_consecutive = max_consecutive.max(current_consecutive);
+/// The run of consecutive dates ending today, or yesterday when there is no
+/// activity yet today.
+fn current_streak(dates: &BTreeSet<NaiveDate>, today: NaiveDate) -> (u32, Option<NaiveDate>) {
+    let yesterday = today - Duration::days(1);
+    let end = if dates.contains(&today) {
+        today
+    } else if dates.contains(&yesterday) {
+        yesterday
+    } else {
+        return (0, None);
+    };
 
-        debug!("Found {} consecutive days of activity", max_consecutive);
-        max_consecutive
+    let mut days = 0;
+    let mut date = end;
+    while dates.contains(&date) {
+        days += 1;
+        date -= Duration::days(1);
     }
+
+    (days, Some(end))
+}
+
+/// Whether `repository` is one of the `repos` a criterion is scoped to. An
+/// empty scope matches every repository.
+fn in_scope(repos: &[String], repository: &str) -> bool {
+    repos.is_empty()
+        || repos
+            .iter()
+            .any(|repo| repo.eq_ignore_ascii_case(repository))
+}
+
+/// Reviews submitted by the user, as seen in `PullRequestReviewEvent`s.
+fn review_contributions_from_events(events: &[GitHubEvent]) -> Vec<GitHubReviewContribution> {
+    events
+        .iter()
+        .filter(|event| event.event_type == "PullRequestReviewEvent")
+        .filter_map(|event| {
+            let review = event.payload.get("review")?;
+            let state = review.get("state")?.as_str()?.to_string();
+            let occurred_at = review
+                .get("submitted_at")
+                .and_then(|submitted_at| serde_json::from_value(submitted_at.clone()).ok())
+                .unwrap_or(event.created_at);
+
+            Some(GitHubReviewContribution {
+                occurred_at,
+                state,
+                repository: event.repo.name.clone(),
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::DateTime;
-    use serde_json::json;
+    use crate::api::types::{RepoPermission, TimeWindow};
+    use crate::github::fixture::test_fixtures;
 
     fn create_test_event(event_type: &str, days_ago: i64, commit_count: usize) -> GitHubEvent {
         let created_at = Utc::now() - Duration::days(days_ago);
         let commits = (0..commit_count)
-            .map(|i| json!({"sha": format!("abc{}", i)}))
+            .map(|i| {
+                json!({
+                    "sha": format!("abc{}-{}", days_ago, i),
+                    "author": {"name": "Test User", "email": "123+testuser@users.noreply.github.com"},
+                    "distinct": true,
+                })
+            })
             .collect::<Vec<_>>();
 
         GitHubEvent {
@@ -171,9 +1618,17 @@ mod tests {
         }
     }
 
+    fn test_author() -> CommitAuthor {
+        CommitAuthor {
+            login: "testuser".to_string(),
+            id: 123,
+            email: Some("testuser@example.com".to_string()),
+        }
+    }
+
     #[test]
     fn test_count_yearly_commits() {
-        let engine = VerificationEngine::new();
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
         let events = vec![
             create_test_event("PushEvent", 30, 2), // 2 commits, 30 days ago
             create_test_event("PushEvent", 100, 3), // 3 commits, 100 days ago
@@ -181,13 +1636,240 @@ mod tests {
             create_test_event("IssueEvent", 50, 0), // Not a push event
         ];
 
-        let result = engine.count_yearly_commits(&events);
+        let window = TimeWindow::default().resolve(Utc::now());
+        let (result, _, daily) = engine.count_yearly_commits(
+            &events,
+            &window,
+            &[],
+            &test_author(),
+            &Attribution::default(),
+            None,
+        );
         assert_eq!(result, 5); // Only commits from within the last year
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily.values().sum::<u32>(), 5);
+    }
+
+    #[test]
+    fn test_count_distinct_commits_by_user() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let window = TimeWindow::default().resolve(Utc::now());
+
+        let mut push = create_test_event("PushEvent", 5, 0);
+        push.payload = json!({
+            "size": 26,
+            "distinct_size": 25,
+            "commits": [
+                {"sha": "a1", "author": {"name": "Dev", "email": "123+testuser@users.noreply.github.com"}, "distinct": true},
+                {"sha": "a2", "author": {"name": "Someone Else", "email": "else@example.com"}, "distinct": true},
+                {"sha": "a3", "author": {"name": "Test", "email": "testuser@users.noreply.github.com"}, "distinct": false},
+                {"sha": "a4", "author": {"name": "Test User", "email": "TESTUSER@example.com"}},
+                // Anyone can commit under the user's login or display name
+                {"sha": "a5", "author": {"name": "testuser", "email": "dev@example.com"}},
+                {"sha": "a6", "author": {"name": "Test User", "email": "spoof@example.com"}},
+            ]
+        });
+        // The same commit pushed again to another branch
+        let mut force_push = create_test_event("PushEvent", 4, 0);
+        force_push.payload = json!({
+            "size": 1,
+            "distinct_size": 1,
+            "commits": [
+                {"sha": "a1", "author": {"name": "Dev", "email": "123+testuser@users.noreply.github.com"}, "distinct": true},
+            ]
+        });
+
+        let (count, evidence, _) = engine.count_yearly_commits(
+            &[push, force_push],
+            &window,
+            &[],
+            &test_author(),
+            &Attribution::default(),
+            None,
+        );
+        assert_eq!(count, 2);
+        assert_eq!(
+            evidence["excluded"],
+            json!({"duplicates": 1, "not_distinct": 1, "other_authors": 3, "unattributed": 0, "unlisted": 20, "anomalous": 0})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_commits_attributed_through_github() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let window = TimeWindow::Range {
+            from: "2026-01-01".parse().unwrap(),
+            to: "2026-10-01".parse().unwrap(),
+        }
+        .resolve(Utc::now());
+        let author = CommitAuthor {
+            login: "octocat".to_string(),
+            id: 583231,
+            email: None,
+        };
+
+        let mut push = create_test_event("PushEvent", 0, 0);
+        push.created_at = "2026-09-12T10:05:00Z".parse().unwrap();
+        push.repo.name = "octocat/Hello-World".to_string();
+        push.payload = json!({
+            "commits": [
+                // Made with a private verified email, which GitHub resolves
+                {"sha": "7fd1a60b01f91b314f59955a4e4d4e80d8edf11d", "author": {"name": "The Octocat", "email": "octo@private.example"}, "distinct": true},
+                {"sha": "0000000000000000000000000000000000000001", "author": {"name": "The Octocat", "email": "spoof@example.com"}, "distinct": true},
+            ]
+        });
+        let events = vec![push];
+
+        let attribution = engine
+            .attribute_commits(
+                "octocat",
+                &events,
+                &window,
+                &[],
+                &author,
+                &ProgressReporter::disabled(),
+            )
+            .await;
+        assert!(attribution
+            .shas
+            .contains("7fd1a60b01f91b314f59955a4e4d4e80d8edf11d"));
+        assert_eq!(attribution.shas.len(), 1);
+        assert!(attribution.unchecked_repos.is_empty());
+
+        let (count, evidence, _) =
+            engine.count_yearly_commits(&events, &window, &[], &author, &attribution, None);
+        assert_eq!(count, 1);
+        assert_eq!(evidence["excluded"]["other_authors"], 1);
+
+        // Commits in repositories that couldn't be looked up aren't dropped
+        // silently
+        let unchecked = Attribution {
+            shas: HashSet::new(),
+            unchecked_repos: HashSet::from(["octocat/Hello-World".to_string()]),
+        };
+        let (count, evidence, _) =
+            engine.count_yearly_commits(&events, &window, &[], &author, &unchecked, None);
+        assert_eq!(count, 0);
+        assert_eq!(evidence["excluded"]["unattributed"], 2);
+    }
+
+    #[test]
+    fn test_repo_scoped_activity() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let window = TimeWindow::default().resolve(Utc::now());
+
+        let mut other_repo = create_test_event("PushEvent", 2, 3);
+        other_repo.repo.name = "testuser/other".to_string();
+        let events = vec![create_test_event("PushEvent", 1, 2), other_repo];
+
+        let repos = vec!["TestUser/TestRepo".to_string()];
+        let (count, _, _) = engine.count_yearly_commits(
+            &events,
+            &window,
+            &repos,
+            &test_author(),
+            &Attribution::default(),
+            None,
+        );
+        assert_eq!(count, 2);
+
+        let options = CriteriaOptions {
+            repos: vec!["testuser/other".to_string()],
+            ..Default::default()
+        };
+        let (days, _, daily) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 1);
+        assert_eq!(daily.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_anomalous_activity_exclusion() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let options = CriteriaOptions::default();
+        let window = options.window().resolve(Utc::now());
+
+        // A scheduled job pushing one commit a day
+        let events: Vec<_> = (1..=20)
+            .map(|days_ago| create_test_event("PushEvent", days_ago, 1))
+            .collect();
+
+        let report = engine
+            .analyze_activity("testuser", &events, &window, &ProgressReporter::disabled())
+            .await
+            .unwrap();
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(
+            report.to_evidence(AnomalyPolicy::Report)["anomalies"][0]["kind"],
+            "regular_timing"
+        );
+
+        let (commits, _, _) = engine.count_yearly_commits(
+            &events,
+            &window,
+            &[],
+            &test_author(),
+            &Attribution::default(),
+            None,
+        );
+        assert_eq!(commits, 20);
+        let (commits, evidence, _) = engine.count_yearly_commits(
+            &events,
+            &window,
+            &[],
+            &test_author(),
+            &Attribution::default(),
+            Some(&report),
+        );
+        assert_eq!(commits, 0);
+        assert_eq!(evidence["excluded"]["anomalous"], 20);
+
+        let (days, _, _) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 20);
+        let (days, _, _) = engine
+            .count_consecutive_days(&events, &options, Some(&report))
+            .unwrap();
+        assert_eq!(days, 0);
+    }
+
+    #[tokio::test]
+    async fn test_anomaly_policy_off_skips_analysis() {
+        let options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2020-01-01".parse().unwrap(),
+                to: "2026-09-30".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+        for (policy, analyzed) in [(AnomalyPolicy::Report, true), (AnomalyPolicy::Off, false)] {
+            let engine =
+                VerificationEngine::new(Arc::new(test_fixtures())).with_anomaly_policy(policy);
+            let outcome = engine
+                .verify_criteria(
+                    "octocat",
+                    VerificationType::YearlyCommits,
+                    1,
+                    &options,
+                    &ProgressReporter::disabled(),
+                )
+                .await
+                .unwrap();
+            let evidence = outcome.evidence.unwrap();
+            assert_eq!(
+                evidence.get("anomalies").is_some(),
+                analyzed,
+                "{:?}",
+                policy
+            );
+        }
     }
 
     #[test]
     fn test_count_consecutive_days() {
-        let engine = VerificationEngine::new();
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
         let events = vec![
             create_test_event("PushEvent", 1, 1), // Yesterday
             create_test_event("PushEvent", 2, 1), // 2 days ago
@@ -196,7 +1878,520 @@ mod tests {
             create_test_event("PushEvent", 6, 1), // 6 days ago
         ];
 
-        let result = engine.count_consecutive_days(&events);
+        let (result, _, _) = engine
+            .count_consecutive_days(&events, &CriteriaOptions::default(), None)
+            .unwrap();
         assert_eq!(result, 3); // Longest streak is 3 consecutive days
     }
+
+    #[test]
+    fn test_current_streak_and_event_filter() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let events = vec![
+            create_test_event("WatchEvent", 1, 0), // Yesterday, not a contribution
+            create_test_event("PushEvent", 2, 1),
+            create_test_event("PushEvent", 10, 1), // Older, longer streak
+            create_test_event("PushEvent", 11, 1),
+            create_test_event("PushEvent", 12, 1),
+        ];
+        let mut options = CriteriaOptions {
+            streak: Some(StreakMode::Current),
+            ..Default::default()
+        };
+
+        let (days, _, _) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 2);
+
+        // Without the WatchEvent the streak ended two days ago
+        options.contribution_events_only = true;
+        let (days, evidence, _) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 0);
+        assert!(evidence["streak_end"].is_null());
+
+        options.streak = Some(StreakMode::Longest);
+        let (days, _, _) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 3);
+    }
+
+    #[test]
+    fn test_consecutive_days_time_zone() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let mut late = create_test_event("PushEvent", 0, 1);
+        late.created_at = "2026-03-01T23:30:00Z".parse().unwrap();
+        let mut early = create_test_event("PushEvent", 0, 1);
+        early.created_at = "2026-03-02T00:30:00Z".parse().unwrap();
+        let events = vec![late, early];
+
+        // Two days in UTC, but the same morning in Tokyo
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2026-03-01".parse().unwrap(),
+                to: "2026-03-02".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+        let (days, _, _) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 2);
+
+        options.time_zone = Some("Asia/Tokyo".to_string());
+        let (days, evidence, _) = engine
+            .count_consecutive_days(&events, &options, None)
+            .unwrap();
+        assert_eq!(days, 1);
+        assert_eq!(evidence["time_zone"], "Asia/Tokyo");
+        assert_eq!(evidence["streak_end"], "2026-03-02");
+    }
+
+    #[tokio::test]
+    async fn test_count_total_stars_and_public_repos() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+
+        let options = CriteriaOptions::default();
+
+        let (stars, _) = engine
+            .count_total_stars("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(stars, 3300);
+        let (repos, _) = engine
+            .count_public_repos("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(repos, 8);
+    }
+
+    #[tokio::test]
+    async fn test_repo_filters() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            exclude_forks: Some(true),
+            exclude_archived: true,
+            ..Default::default()
+        };
+
+        let (stars, evidence) = engine
+            .count_total_stars("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(stars, 2800);
+        assert_eq!(evidence["repositories_counted"], 1);
+        assert_eq!(evidence["excluded"]["forks"], 1);
+        assert_eq!(evidence["excluded"]["archived"], 1);
+
+        // Only Hello-World has recorded commits by octocat
+        options = CriteriaOptions {
+            exclude_repos_without_commits: true,
+            ..Default::default()
+        };
+        let (repos, evidence) = engine
+            .count_public_repos("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(repos, 1);
+        assert_eq!(evidence["repositories_total"], 3);
+        assert_eq!(evidence["excluded"]["without_commits"], 2);
+
+        // Every fixture repository is years old
+        options = CriteriaOptions {
+            min_repo_age_days: Some(365),
+            ..Default::default()
+        };
+        let (stars, _) = engine
+            .count_total_stars("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(stars, 3300);
+    }
+
+    #[tokio::test]
+    async fn test_account_age_and_followers() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+
+        // octocat was created on 2011-01-25
+        let (age_days, evidence) = engine
+            .count_account_age_days("octocat", &progress)
+            .await
+            .unwrap();
+        assert!(age_days > 5000);
+        assert_eq!(evidence["created_at"], "2011-01-25T18:44:36Z");
+        let (followers, evidence) = engine.count_followers("octocat", &progress).await.unwrap();
+        assert_eq!(followers, 4000);
+        assert_eq!(evidence, json!({"followers": 4000}));
+
+        // Recordings made before followers were fetched still load
+        let (followers, _) = engine
+            .count_followers("quiet-maintainer", &progress)
+            .await
+            .unwrap();
+        assert_eq!(followers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_count_repo_commits() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2020-01-01".parse().unwrap(),
+                to: "2026-09-30".parse().unwrap(),
+            }),
+            repos: vec![
+                "octocat/Hello-World".to_string(),
+                "octocat/hello-world-fork".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        // The 2014 commit is outside the window and the fork shares one
+        // commit with its upstream
+        let (count, evidence) = engine
+            .count_repo_commits("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(
+            evidence["by_repository"],
+            json!({"octocat/Hello-World": 3, "octocat/hello-world-fork": 1})
+        );
+
+        // Merged pull requests and reviews honour the same scope
+        options.repos = vec!["rust-lang/rust".to_string()];
+        let (count, _) = engine
+            .count_merged_pull_requests("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        let (count, _) = engine
+            .count_code_reviews("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_measure_signed_commits() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2020-01-01".parse().unwrap(),
+                to: "2026-09-30".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+
+        // Two of the three commits in the window are signed; the fork is
+        // not sampled
+        let (percentage, evidence) = engine
+            .measure_signed_commits("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(percentage, 66);
+        assert_eq!(evidence["sample_size"], 3);
+        assert_eq!(evidence["verified_commits"], 2);
+
+        // The two most recent commits are both signed
+        options.sample_size = Some(2);
+        let (percentage, _) = engine
+            .measure_signed_commits("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(percentage, 100);
+
+        // No commits at all never meets the criterion
+        let (percentage, evidence) = engine
+            .measure_signed_commits("quiet-maintainer", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(percentage, 0);
+        assert_eq!(evidence["sample_size"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_language_criteria() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            language: Some("rust".to_string()),
+            ..Default::default()
+        };
+
+        // Forks are excluded unless asked otherwise
+        let (count, _) = engine
+            .count_language_repos("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        let (bytes, evidence) = engine
+            .count_language_bytes("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(bytes, 312_000);
+        assert_eq!(evidence["repositories_scanned"], 2);
+
+        options.exclude_forks = Some(false);
+        let (count, _) = engine
+            .count_language_repos("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        let (bytes, _) = engine
+            .count_language_bytes("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(bytes, 1_212_000);
+    }
+
+    #[tokio::test]
+    async fn test_org_membership_and_repo_permission() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            org: Some("GitHub".to_string()),
+            repo: Some("octo-org/widgets".to_string()),
+            ..Default::default()
+        };
+
+        let (is_member, evidence) = engine
+            .check_org_membership("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert!(is_member);
+        assert_eq!(evidence["organization"], "GitHub");
+
+        options.org = Some("rust-lang".to_string());
+        let (is_member, _) = engine
+            .check_org_membership("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert!(!is_member);
+
+        // octocat maintains octo-org/widgets
+        for (permission, expected) in [
+            (None, true),
+            (Some(RepoPermission::Maintain), true),
+            (Some(RepoPermission::Admin), false),
+        ] {
+            options.permission = permission;
+            let (has_permission, _) = engine
+                .check_repo_permission("octocat", &options, &progress)
+                .await
+                .unwrap();
+            assert_eq!(has_permission, expected, "{:?}", permission);
+        }
+
+        options.repo = Some("octo-org/missing".to_string());
+        assert!(matches!(
+            engine
+                .check_repo_permission("octocat", &options, &progress)
+                .await,
+            Err(AppError::GitHub(GitHubError::RepositoryNotFound(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_star_and_repo_criteria_without_events() {
+        // Regression: these criteria used to take the login from the first
+        // event, so a user with no recent public activity always failed
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let options = CriteriaOptions::default();
+        let progress = ProgressReporter::disabled();
+
+        for (verification_type, threshold, expected) in [
+            (VerificationType::TotalStars, 5000, true),
+            (VerificationType::PublicRepos, 2, true),
+            (VerificationType::YearlyCommits, 1, false),
+        ] {
+            let outcome = engine
+                .verify_criteria(
+                    "quiet-maintainer",
+                    verification_type,
+                    threshold,
+                    &options,
+                    &progress,
+                )
+                .await
+                .unwrap();
+            assert_eq!(outcome.meets_criteria, expected, "{:?}", verification_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_merged_pull_requests() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2020-01-01".parse().unwrap(),
+                to: "2026-09-30".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+
+        // The 2014 pull request is outside the window and one result was
+        // never merged
+        let (count, evidence) = engine
+            .count_merged_pull_requests("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(
+            evidence["by_organization"],
+            json!({"octocat": 1, "rust-lang": 2, "tokio-rs": 1})
+        );
+
+        options.exclude_own_repos = true;
+        let (count, evidence) = engine
+            .count_merged_pull_requests("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            evidence["by_organization"],
+            json!({"rust-lang": 2, "tokio-rs": 1})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_opened_issues() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2020-01-01".parse().unwrap(),
+                to: "2026-09-30".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+
+        // The 2014 issue is outside the window
+        let (count, evidence) = engine
+            .count_opened_issues("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            evidence["by_organization"],
+            json!({"octocat": 1, "rust-lang": 1, "tokio-rs": 1})
+        );
+
+        options.repos = vec!["rust-lang/rust".to_string()];
+        let (count, _) = engine
+            .count_opened_issues("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_time_windows() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::CalendarYear { year: 2025 }),
+            ..Default::default()
+        };
+
+        // Only the tokio-rs pull request was merged in 2025
+        let (count, evidence) = engine
+            .count_merged_pull_requests("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(evidence["window"]["type"], "calendar_year");
+        assert_eq!(evidence["window"]["from"], "2025-01-01T00:00:00Z");
+        assert_eq!(evidence["window"]["to"], "2026-01-01T00:00:00Z");
+
+        // Range ends are inclusive days
+        options.window = Some(TimeWindow::Range {
+            from: "2026-01-01".parse().unwrap(),
+            to: "2026-02-01".parse().unwrap(),
+        });
+        let (count, evidence) = engine
+            .count_merged_pull_requests("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            evidence["by_organization"],
+            json!({"octocat": 1, "rust-lang": 1})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_code_reviews() {
+        let engine = VerificationEngine::new(Arc::new(test_fixtures()));
+        let progress = ProgressReporter::disabled();
+        let mut options = CriteriaOptions {
+            window: Some(TimeWindow::Range {
+                from: "2020-01-01".parse().unwrap(),
+                to: "2026-09-30".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+
+        // The 2014 review is outside the window
+        let (count, evidence) = engine
+            .count_code_reviews("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(evidence["source"], "graphql");
+        assert_eq!(evidence["truncated"], false);
+        assert_eq!(
+            evidence["by_state"],
+            json!({"APPROVED": 2, "CHANGES_REQUESTED": 1, "COMMENTED": 1})
+        );
+
+        options.substantive_reviews_only = true;
+        options.exclude_own_repos = true;
+        let (count, evidence) = engine
+            .count_code_reviews("octocat", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            evidence["by_state"],
+            json!({"APPROVED": 1, "CHANGES_REQUESTED": 1})
+        );
+
+        // Without recorded GraphQL data the events feed is used instead
+        let (count, evidence) = engine
+            .count_code_reviews("quiet-maintainer", &options, &progress)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(evidence["source"], "events");
+        assert_eq!(evidence["truncated"], false);
+    }
+
+    #[test]
+    fn test_review_contributions_from_events() {
+        let mut review = create_test_event("PullRequestReviewEvent", 3, 0);
+        review.payload = json!({
+            "action": "created",
+            "review": {"state": "approved", "submitted_at": "2026-01-02T03:04:05Z"}
+        });
+        let events = vec![review, create_test_event("PushEvent", 1, 2)];
+
+        let reviews = review_contributions_from_events(&events);
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].state, "approved");
+        assert_eq!(reviews[0].repository, "testuser/testrepo");
+        assert_eq!(
+            reviews[0].occurred_at.to_rfc3339(),
+            "2026-01-02T03:04:05+00:00"
+        );
+    }
 }
@@ -0,0 +1,215 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// Cell size and spacing of the rendered graph, in pixels
+const CELL_SIZE: i64 = 10;
+const CELL_STEP: i64 = 12;
+const MARGIN: i64 = 10;
+const CAPTION_HEIGHT: i64 = 20;
+// GitHub's contribution graph palette, from no activity to the busiest cells
+const PALETTE: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// Activity counted per calendar day.
+pub type DailyCounts = BTreeMap<NaiveDate, u32>;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramGranularity {
+    Day,
+    /// Weeks starting on Monday
+    Week,
+}
+
+/// Activity over a window, one count per day or week. Bucket `i` starts `i`
+/// days or weeks after `start`; weekly histograms start on the Monday of the
+/// window's first week.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActivityHistogram {
+    pub granularity: HistogramGranularity,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub counts: Vec<u32>,
+}
+
+impl ActivityHistogram {
+    /// Buckets `daily` over the inclusive range `start..=end`; days outside
+    /// the range are ignored.
+    pub fn new(
+        granularity: HistogramGranularity,
+        start: NaiveDate,
+        end: NaiveDate,
+        daily: &DailyCounts,
+    ) -> Self {
+        let start = match granularity {
+            HistogramGranularity::Day => start,
+            HistogramGranularity::Week => week_start(start),
+        };
+        let bucket_days = match granularity {
+            HistogramGranularity::Day => 1,
+            HistogramGranularity::Week => 7,
+        };
+        let len = ((end - start).num_days().max(0) / bucket_days + 1) as usize;
+
+        let mut counts = vec![0; len];
+        for (&date, &count) in daily.range(start..=end) {
+            counts[((date - start).num_days() / bucket_days) as usize] += count;
+        }
+
+        Self {
+            granularity,
+            start,
+            end,
+            counts,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    fn bucket_start(&self, index: usize) -> NaiveDate {
+        let days = match self.granularity {
+            HistogramGranularity::Day => index as i64,
+            HistogramGranularity::Week => index as i64 * 7,
+        };
+        self.start + Duration::days(days)
+    }
+
+    /// Renders the histogram as a contribution-graph style heatmap: daily
+    /// histograms get a column per week and a row per weekday, weekly ones a
+    /// single row.
+    pub fn to_svg(&self, caption: &str) -> String {
+        let (columns, rows) = match self.granularity {
+            HistogramGranularity::Day => {
+                let first = week_start(self.start);
+                ((self.end - first).num_days() / 7 + 1, 7)
+            }
+            HistogramGranularity::Week => (self.counts.len() as i64, 1),
+        };
+        let width = (MARGIN * 2 + columns * CELL_STEP).max(240);
+        let height = MARGIN * 2 + CAPTION_HEIGHT + rows * CELL_STEP;
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="sans-serif" font-size="11">"#,
+            width, height, width, height
+        );
+        let _ = write!(
+            svg,
+            r##"<text x="{}" y="{}" fill="#24292f">{}</text>"##,
+            MARGIN,
+            MARGIN + 11,
+            escape(caption)
+        );
+
+        for (index, &count) in self.counts.iter().enumerate() {
+            let date = self.bucket_start(index);
+            let (column, row) = match self.granularity {
+                HistogramGranularity::Day => (
+                    (date - week_start(self.start)).num_days() / 7,
+                    date.weekday().num_days_from_monday() as i64,
+                ),
+                HistogramGranularity::Week => (index as i64, 0),
+            };
+            let _ = write!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="2" fill="{}"><title>{}: {}</title></rect>"#,
+                MARGIN + column * CELL_STEP,
+                MARGIN + CAPTION_HEIGHT + row * CELL_STEP,
+                CELL_SIZE,
+                CELL_SIZE,
+                PALETTE[level(count, max)],
+                date,
+                count
+            );
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// The Monday on or before `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Color level of a cell: 0 for no activity, then quartiles of the maximum.
+fn level(count: u32, max: u32) -> usize {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    (((count as u64 * 4).div_ceil(max as u64)) as usize).clamp(1, PALETTE.len() - 1)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let daily: DailyCounts = [
+            (date("2025-01-01"), 2),
+            (date("2025-01-05"), 1),
+            (date("2025-01-06"), 4),
+            (date("2025-02-01"), 9),
+        ]
+        .into_iter()
+        .collect();
+
+        let by_day = ActivityHistogram::new(
+            HistogramGranularity::Day,
+            date("2025-01-01"),
+            date("2025-01-07"),
+            &daily,
+        );
+        assert_eq!(by_day.counts, vec![2, 0, 0, 0, 1, 4, 0]);
+        assert_eq!(by_day.total(), 7);
+
+        // 2025-01-01 is a Wednesday; its week starts on 2024-12-30
+        let by_week = ActivityHistogram::new(
+            HistogramGranularity::Week,
+            date("2025-01-01"),
+            date("2025-01-14"),
+            &daily,
+        );
+        assert_eq!(by_week.start, date("2024-12-30"));
+        assert_eq!(by_week.counts, vec![3, 4, 0]);
+    }
+
+    #[test]
+    fn test_histogram_svg() {
+        let daily: DailyCounts = [(date("2025-01-01"), 4), (date("2025-01-02"), 1)]
+            .into_iter()
+            .collect();
+        let histogram = ActivityHistogram::new(
+            HistogramGranularity::Day,
+            date("2025-01-01"),
+            date("2025-01-14"),
+            &daily,
+        );
+
+        let svg = histogram.to_svg("octocat <yearly_commits>");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("octocat &lt;yearly_commits&gt;"));
+        assert_eq!(svg.matches("<rect").count(), 14);
+        assert!(svg.contains(r##"fill="#216e39"><title>2025-01-01: 4</title>"##));
+        assert!(svg.contains(r##"fill="#9be9a8"><title>2025-01-02: 1</title>"##));
+    }
+}
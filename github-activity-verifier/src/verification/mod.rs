@@ -1 +1,4 @@
+pub mod anomaly;
 pub mod engine;
+pub mod histogram;
+pub mod proof;
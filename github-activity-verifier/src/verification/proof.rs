@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::api::types::{CriteriaOptions, DisclosureMode, VerificationType};
+use crate::verification::anomaly::AnomalyPolicy;
+use crate::verification::histogram::ActivityHistogram;
+
+/// The fields of a proof that its hash covers. The verifier builds it when
+/// issuing a proof; offline verifiers rebuild it from the proof they were
+/// given.
+#[derive(Debug, Clone)]
+pub struct ProofData<'a> {
+    pub username: &'a str,
+    pub verification_type: VerificationType,
+    pub options: &'a CriteriaOptions,
+    pub disclosure: DisclosureMode,
+    pub threshold: u32,
+    pub meets_criteria: bool,
+    pub verified_at: DateTime<Utc>,
+    pub histogram: Option<&'a ActivityHistogram>,
+    pub anomaly_policy: Option<AnomalyPolicy>,
+    pub status_list_index: u32,
+    pub status_list_credential: &'a str,
+}
+
+impl ProofData<'_> {
+    /// The string whose SHA-256 is the proof hash. Criterion options are
+    /// appended as JSON when present, so proofs made with different options
+    /// never share a hash; bucketed proofs also bind the bucket they attest,
+    /// and the attested histogram is appended as JSON. Commit-based proofs
+    /// bind the anomaly policy they were counted under. The status list entry
+    /// comes last, so the revocation bit a verifier checks is the one that
+    /// was attested.
+    pub fn canonical(&self) -> String {
+        let mut proof_data = format!(
+            "{}:{}:{}:{}",
+            self.username,
+            serde_json::to_string(&self.verification_type).unwrap(),
+            self.meets_criteria,
+            self.verified_at.timestamp()
+        );
+        if !self.options.is_default() {
+            proof_data.push(':');
+            proof_data.push_str(&serde_json::to_string(self.options).unwrap());
+        }
+        if self.disclosure == DisclosureMode::Bucketed {
+            proof_data.push_str(&format!(":bucket:{}", self.threshold));
+        }
+        if let Some(histogram) = self.histogram {
+            proof_data.push_str(":histogram:");
+            proof_data.push_str(&serde_json::to_string(histogram).unwrap());
+        }
+        if let Some(policy) = self.anomaly_policy {
+            proof_data.push_str(":anomaly_policy:");
+            proof_data.push_str(&serde_json::to_string(&policy).unwrap());
+        }
+        proof_data.push_str(&format!(
+            ":status:{}#{}",
+            self.status_list_credential, self.status_list_index
+        ));
+        proof_data
+    }
+
+    /// Hex SHA-256 of the canonical string.
+    pub fn hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.canonical().as_bytes()))
+    }
+}
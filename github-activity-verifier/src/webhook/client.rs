@@ -0,0 +1,394 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Serialize;
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::api::types::{VerificationResult, VerificationType};
+
+const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_LOG_ENTRIES: usize = 1000;
+
+pub const SIGNATURE_HEADER: &str = "X-Verifier-Signature";
+pub const DELIVERY_HEADER: &str = "X-Verifier-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Verifier-Timestamp";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub event: &'static str,
+    pub proof_hash: String,
+    pub username: String,
+    pub verification_type: VerificationType,
+    pub threshold: u32,
+    pub meets_criteria: bool,
+    pub verified_at: DateTime<Utc>,
+}
+
+impl WebhookPayload {
+    pub fn verification_completed(proof_hash: &str, result: &VerificationResult) -> Self {
+        Self {
+            event: "verification.completed",
+            proof_hash: proof_hash.to_string(),
+            username: result.username.clone(),
+            verification_type: result.verification_type,
+            threshold: result.threshold,
+            meets_criteria: result.meets_criteria,
+            verified_at: result.verified_at,
+        }
+    }
+}
+
+/// One delivery attempt, as recorded in the delivery log.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub delivery_id: String,
+    pub proof_hash: String,
+    pub callback_url: String,
+    pub attempt: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub delivered: bool,
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// The recorded attempts, plus the number of deliveries still running per
+/// proof. A delivery only records attempts while its proof is in flight, so
+/// forgetting a proof's deliveries also stops its pending retries.
+#[derive(Default)]
+struct DeliveryLog {
+    entries: VecDeque<WebhookDelivery>,
+    in_flight: HashMap<String, usize>,
+}
+
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: Client,
+    secret: Option<String>,
+    allowed_hosts: Vec<String>,
+    deliveries: Arc<RwLock<DeliveryLog>>,
+}
+
+impl WebhookClient {
+    pub fn new(secret: Option<String>, allowed_hosts: Vec<String>) -> Self {
+        // Redirects aren't followed: an allowed host could otherwise send the
+        // signed request on to any other address
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            secret,
+            allowed_hosts: allowed_hosts
+                .into_iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+            deliveries: Arc::new(RwLock::new(DeliveryLog::default())),
+        }
+    }
+
+    /// Callbacks are only accepted when a signing secret and at least one
+    /// allowed host are configured.
+    pub fn is_enabled(&self) -> bool {
+        self.secret.is_some() && !self.allowed_hosts.is_empty()
+    }
+
+    pub fn allowed_hosts(&self) -> &[String] {
+        &self.allowed_hosts
+    }
+
+    /// HMAC-SHA256 of `<timestamp>.<body>`, formatted as `sha256=<hex>`.
+    /// Signing the timestamp lets receivers reject replayed deliveries.
+    pub fn sign(&self, timestamp: i64, body: &[u8]) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{}.", timestamp).as_bytes());
+        mac.update(body);
+        Some(format!("sha256={:x}", mac.finalize().into_bytes()))
+    }
+
+    /// Delivers the payload in the background, retrying with exponential backoff.
+    pub fn dispatch(&self, callback_url: String, payload: WebhookPayload) {
+        let client = self.clone();
+        client.track(&payload.proof_hash);
+        tokio::spawn(async move {
+            let proof_hash = payload.proof_hash.clone();
+            client.deliver(callback_url, payload).await;
+            client.untrack(&proof_hash);
+        });
+    }
+
+    fn track(&self, proof_hash: &str) {
+        let mut deliveries = self.deliveries.write().unwrap();
+        *deliveries
+            .in_flight
+            .entry(proof_hash.to_string())
+            .or_default() += 1;
+    }
+
+    fn untrack(&self, proof_hash: &str) {
+        let mut deliveries = self.deliveries.write().unwrap();
+        if let Some(count) = deliveries.in_flight.get_mut(proof_hash) {
+            *count -= 1;
+            if *count == 0 {
+                deliveries.in_flight.remove(proof_hash);
+            }
+        }
+    }
+
+    async fn deliver(&self, callback_url: String, payload: WebhookPayload) {
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(err) => {
+                warn!("Failed to serialize webhook payload: {}", err);
+                return;
+            }
+        };
+        if self.secret.is_none() {
+            warn!(
+                "Webhook secret not configured, dropping delivery to {}",
+                callback_url
+            );
+            return;
+        }
+
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            if !self.is_in_flight(&payload.proof_hash) {
+                debug!("Webhook {} cancelled, its proof was erased", delivery_id);
+                return;
+            }
+            debug!(
+                "Delivering webhook {} to {} (attempt {})",
+                delivery_id, callback_url, attempt
+            );
+
+            let timestamp = Utc::now().timestamp();
+            let signature = self.sign(timestamp, &body).unwrap_or_default();
+            let response = self
+                .client
+                .post(&callback_url)
+                .header("Content-Type", "application/json")
+                .header(SIGNATURE_HEADER, signature)
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(DELIVERY_HEADER, &delivery_id)
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (status_code, error, delivered, retryable) = match response {
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status.is_server_error()
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT;
+                    let error = status
+                        .is_redirection()
+                        .then(|| "redirects are not followed".to_string());
+                    (Some(status.as_u16()), error, status.is_success(), retryable)
+                }
+                Err(err) => (None, Some(err.to_string()), false, true),
+            };
+
+            let recorded = self.record(WebhookDelivery {
+                delivery_id: delivery_id.clone(),
+                proof_hash: payload.proof_hash.clone(),
+                callback_url: callback_url.clone(),
+                attempt,
+                status_code,
+                error,
+                delivered,
+                attempted_at: Utc::now(),
+            });
+            if !recorded {
+                debug!("Webhook {} cancelled, its proof was erased", delivery_id);
+                return;
+            }
+
+            if delivered {
+                info!("Webhook {} delivered to {}", delivery_id, callback_url);
+                return;
+            }
+            if !retryable || attempt == MAX_ATTEMPTS {
+                break;
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+
+        warn!(
+            "Webhook {} to {} failed, giving up",
+            delivery_id, callback_url
+        );
+    }
+
+    fn is_in_flight(&self, proof_hash: &str) -> bool {
+        self.deliveries
+            .read()
+            .unwrap()
+            .in_flight
+            .contains_key(proof_hash)
+    }
+
+    /// Records an attempt, unless the proof's deliveries were forgotten
+    /// since the delivery started.
+    fn record(&self, delivery: WebhookDelivery) -> bool {
+        let mut deliveries = self.deliveries.write().unwrap();
+        if !deliveries.in_flight.contains_key(&delivery.proof_hash) {
+            return false;
+        }
+        if deliveries.entries.len() >= MAX_LOG_ENTRIES {
+            deliveries.entries.pop_front();
+        }
+        deliveries.entries.push_back(delivery);
+        true
+    }
+
+    /// Removes the delivery log entries of the given proofs and returns how
+    /// many were removed. Their deliveries still running stop before the
+    /// next attempt and record nothing more.
+    pub fn forget_deliveries(&self, proof_hashes: &[String]) -> usize {
+        let mut deliveries = self.deliveries.write().unwrap();
+        for proof_hash in proof_hashes {
+            deliveries.in_flight.remove(proof_hash);
+        }
+        let before = deliveries.entries.len();
+        deliveries
+            .entries
+            .retain(|delivery| !proof_hashes.contains(&delivery.proof_hash));
+        before - deliveries.entries.len()
+    }
+
+    pub fn deliveries_for(&self, proof_hash: &str) -> Vec<WebhookDelivery> {
+        self.deliveries
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|delivery| delivery.proof_hash == proof_hash)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_client_configuration() {
+        let client = WebhookClient::new(
+            Some("secret".to_string()),
+            vec![" Hooks.Example.com ".to_string(), "".to_string()],
+        );
+        assert!(client.is_enabled());
+        assert_eq!(client.allowed_hosts(), ["hooks.example.com"]);
+
+        assert!(!WebhookClient::new(None, vec!["hooks.example.com".to_string()]).is_enabled());
+        assert!(!WebhookClient::new(Some("secret".to_string()), vec![]).is_enabled());
+    }
+
+    #[test]
+    fn test_sign() {
+        let client = WebhookClient::new(Some("key".to_string()), vec![]);
+        let signature = client.sign(1_700_000_000, b"{}").unwrap();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(b"1700000000.{}");
+        assert_eq!(
+            signature,
+            format!("sha256={:x}", mac.finalize().into_bytes())
+        );
+        // The same body sent at another time has another signature
+        assert_ne!(client.sign(1_700_000_001, b"{}").unwrap(), signature);
+
+        assert!(WebhookClient::new(None, vec![]).sign(0, b"body").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_not_followed() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let callback_url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await.unwrap();
+            socket
+                .write_all(
+                    b"HTTP/1.1 307 Temporary Redirect\r\nLocation: http://169.254.169.254/\r\nContent-Length: 0\r\n\r\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        let client = WebhookClient::new(Some("key".to_string()), vec!["127.0.0.1".to_string()]);
+        let payload = WebhookPayload {
+            event: "verification.completed",
+            proof_hash: "a".repeat(64),
+            username: "octocat".to_string(),
+            verification_type: VerificationType::TotalStars,
+            threshold: 100,
+            meets_criteria: true,
+            verified_at: Utc::now(),
+        };
+        client.track(&payload.proof_hash);
+        client.deliver(callback_url, payload).await;
+        server.await.unwrap();
+
+        let deliveries = client.deliveries_for(&"a".repeat(64));
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status_code, Some(307));
+        assert!(!deliveries[0].delivered);
+    }
+
+    #[tokio::test]
+    async fn test_forgotten_deliveries_stop() {
+        let client = WebhookClient::new(Some("key".to_string()), vec!["127.0.0.1".to_string()]);
+        let payload = WebhookPayload {
+            event: "verification.completed",
+            proof_hash: "a".repeat(64),
+            username: "octocat".to_string(),
+            verification_type: VerificationType::TotalStars,
+            threshold: 100,
+            meets_criteria: true,
+            verified_at: Utc::now(),
+        };
+        let attempt = |attempt| WebhookDelivery {
+            delivery_id: "delivery".to_string(),
+            proof_hash: "a".repeat(64),
+            callback_url: "http://127.0.0.1:1/hook".to_string(),
+            attempt,
+            status_code: Some(503),
+            error: None,
+            delivered: false,
+            attempted_at: Utc::now(),
+        };
+
+        client.track(&payload.proof_hash);
+        assert!(client.record(attempt(1)));
+        assert_eq!(client.forget_deliveries(&["a".repeat(64)]), 1);
+
+        // A retry still in the background records nothing and sends nothing
+        assert!(!client.record(attempt(2)));
+        client
+            .deliver("http://127.0.0.1:1/hook".to_string(), payload)
+            .await;
+        client.untrack(&"a".repeat(64));
+        assert!(client.deliveries_for(&"a".repeat(64)).is_empty());
+    }
+}
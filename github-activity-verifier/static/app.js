@@ -1,5 +1,25 @@
 // GitHub Activity Verifier - Frontend JavaScript
 
+// 検証タイプごとのラベルとデフォルト閾値
+const VERIFICATION_TYPES = {
+    'yearly_commits': { label: 'Commits/Year', defaultThreshold: 365 },
+    'consecutive_days': { label: 'Days Streak', defaultThreshold: 100 },
+    'total_stars': { label: 'Total Stars', defaultThreshold: 1000 },
+    'public_repos': { label: 'Public Repos', defaultThreshold: 10 },
+    'merged_pull_requests': { label: 'Merged PRs', defaultThreshold: 10 },
+    'opened_issues': { label: 'Opened Issues', defaultThreshold: 10 },
+    'code_reviews': { label: 'Code Reviews', defaultThreshold: 50 },
+    'account_age_days': { label: 'Days Account Age', defaultThreshold: 365 },
+    'followers': { label: 'Followers', defaultThreshold: 100 },
+    'repo_commits': { label: 'Repo Commits', defaultThreshold: 50 },
+    'language_repos': { label: 'Repos in Language', defaultThreshold: 10 },
+    'signed_commits': { label: '% Signed Commits', defaultThreshold: 80, maxThreshold: 100 },
+    'language_bytes': { label: 'Bytes in Language', defaultThreshold: 512000, maxThreshold: 1000000000 },
+    // 閾値を持たない（はい/いいえで判定する）検証タイプ
+    'org_membership': { label: 'Org Member', defaultThreshold: 1, boolean: true },
+    'repo_permission': { label: 'Repo Push Access', defaultThreshold: 1, boolean: true }
+};
+
 class GitHubVerifier {
     constructor() {
         this.currentState = 'form'; // 'form' | 'loading' | 'result' | 'error'
@@ -48,6 +68,24 @@ class GitHubVerifier {
         document.getElementById('threshold').addEventListener('input', () => {
             this.updateVerificationLabels();
         });
+        
+        // 範囲のみ開示する場合はthresholdを無効化
+        document.getElementById('disclosure').addEventListener('change', () => {
+            const bucketed = document.getElementById('disclosure').value === 'bucketed';
+            document.getElementById('threshold').disabled = bucketed;
+        });
+        
+        // 期間の種類に応じて入力欄を切り替え
+        document.getElementById('window-type').addEventListener('change', () => {
+            this.updateWindowInputs();
+        });
+    }
+    
+    updateWindowInputs() {
+        const windowType = document.getElementById('window-type').value;
+        document.getElementById('window-days').style.display = windowType === 'rolling' ? 'block' : 'none';
+        document.getElementById('window-year').style.display = windowType === 'calendar_year' ? 'block' : 'none';
+        document.getElementById('window-range').style.display = windowType === 'range' ? 'block' : 'none';
     }
     
     initFromURL() {
@@ -68,14 +106,7 @@ class GitHubVerifier {
         const selectedType = document.querySelector('input[name="verification-type"]:checked').value;
         const thresholdInput = document.getElementById('threshold');
         
-        const defaults = {
-            'yearly_commits': '365',
-            'consecutive_days': '100',
-            'total_stars': '1000',
-            'public_repos': '10'
-        };
-        
-        thresholdInput.placeholder = `Default: ${defaults[selectedType]}`;
+        thresholdInput.placeholder = `Default: ${VERIFICATION_TYPES[selectedType].defaultThreshold}`;
     }
     
     updateVerificationLabels() {
@@ -83,31 +114,16 @@ class GitHubVerifier {
         const thresholdInput = document.getElementById('threshold');
         const customThreshold = thresholdInput.value;
         
-        const defaults = {
-            'yearly_commits': 365,
-            'consecutive_days': 100,
-            'total_stars': 1000,
-            'public_repos': 10
-        };
-        
-        const typeLabels = {
-            'yearly_commits': 'Commits/Year',
-            'consecutive_days': 'Days Streak',
-            'total_stars': 'Total Stars',
-            'public_repos': 'Public Repos'
-        };
-        
-        // 使用する閾値を決定
-        const threshold = customThreshold ? parseInt(customThreshold) : defaults[selectedType];
-        
         // ラジオボタンのラベルを更新
         document.querySelectorAll('input[name="verification-type"]').forEach(radio => {
             const span = radio.nextElementSibling;
             const radioType = radio.value;
             const radioThreshold = (radioType === selectedType && customThreshold) ? 
-                parseInt(customThreshold) : defaults[radioType];
+                parseInt(customThreshold) : VERIFICATION_TYPES[radioType].defaultThreshold;
             
-            span.textContent = `${radioThreshold}+ ${typeLabels[radioType]}`;
+            span.textContent = VERIFICATION_TYPES[radioType].boolean ?
+                VERIFICATION_TYPES[radioType].label :
+                `${radioThreshold}+ ${VERIFICATION_TYPES[radioType].label}`;
         });
     }
     
@@ -119,9 +135,10 @@ class GitHubVerifier {
         }
         
         this.showLoading();
+        this.resetProgressSteps();
         
         try {
-            const response = await fetch('/api/verify', {
+            const response = await fetch('/api/verify/stream', {
                 method: 'POST',
                 headers: {
                     'Content-Type': 'application/json'
@@ -129,12 +146,12 @@ class GitHubVerifier {
                 body: JSON.stringify(formData)
             });
             
-            const result = await response.json();
-            
             if (!response.ok) {
-                throw new Error(result.error || 'Verification failed');
+                const error = await response.json();
+                throw new Error(error.error || 'Verification failed');
             }
             
+            const result = await this.readVerificationStream(response);
             this.handleVerificationSuccess(result);
             
         } catch (error) {
@@ -143,6 +160,80 @@ class GitHubVerifier {
         }
     }
     
+    // SSEストリームを読み取り、progressイベントを表示してresultを返す
+    async readVerificationStream(response) {
+        const reader = response.body.getReader();
+        const decoder = new TextDecoder();
+        let buffer = '';
+        
+        while (true) {
+            const { done, value } = await reader.read();
+            if (done) {
+                break;
+            }
+            buffer += decoder.decode(value, { stream: true });
+            
+            let boundary;
+            while ((boundary = buffer.indexOf('\n\n')) !== -1) {
+                const frame = buffer.slice(0, boundary);
+                buffer = buffer.slice(boundary + 2);
+                
+                const { event, data } = this.parseSSEFrame(frame);
+                if (event === 'progress') {
+                    this.showProgressStep(data);
+                } else if (event === 'result') {
+                    return data;
+                } else if (event === 'error') {
+                    throw new Error(data.error || 'Verification failed');
+                }
+            }
+        }
+        
+        throw new Error('Verification stream ended unexpectedly');
+    }
+    
+    parseSSEFrame(frame) {
+        let event = 'message';
+        const dataLines = [];
+        
+        frame.split('\n').forEach(line => {
+            if (line.startsWith('event:')) {
+                event = line.slice(6).trim();
+            } else if (line.startsWith('data:')) {
+                dataLines.push(line.slice(5).trim());
+            }
+        });
+        
+        return { event, data: dataLines.length ? JSON.parse(dataLines.join('\n')) : null };
+    }
+    
+    resetProgressSteps() {
+        document.getElementById('progress-steps').innerHTML = '';
+        this.currentProgressPhase = null;
+    }
+    
+    showProgressStep(progress) {
+        const list = document.getElementById('progress-steps');
+        const text = progress.count !== undefined ?
+            `${progress.message} (${progress.count})` : progress.message;
+        
+        // 同じフェーズの場合は最後の項目を更新
+        if (this.currentProgressPhase === progress.phase && list.lastElementChild) {
+            list.lastElementChild.textContent = text;
+            return;
+        }
+        
+        if (list.lastElementChild) {
+            list.lastElementChild.className = 'done';
+        }
+        
+        const item = document.createElement('li');
+        item.className = 'active';
+        item.textContent = text;
+        list.appendChild(item);
+        this.currentProgressPhase = progress.phase;
+    }
+    
     getFormData() {
         const username = document.getElementById('github-username').value.trim();
         const verificationType = document.querySelector('input[name="verification-type"]:checked').value;
@@ -153,10 +244,93 @@ class GitHubVerifier {
             verification_type: verificationType
         };
         
-        if (thresholdInput) {
+        if (document.getElementById('disclosure').value === 'bucketed') {
+            formData.disclosure = 'bucketed';
+        } else if (thresholdInput) {
             formData.threshold = parseInt(thresholdInput);
         }
         
+        // 詳細オプション（指定された場合のみ送信）
+        const options = {};
+        const windowType = document.getElementById('window-type').value;
+        const windowDays = document.getElementById('window-days').value;
+        if (windowType === 'rolling' && windowDays) {
+            options.window = { type: 'rolling', days: parseInt(windowDays) };
+        } else if (windowType === 'calendar_year') {
+            options.window = { type: 'calendar_year', year: parseInt(document.getElementById('window-year').value) };
+        } else if (windowType === 'range') {
+            options.window = {
+                type: 'range',
+                from: document.getElementById('window-from').value,
+                to: document.getElementById('window-to').value
+            };
+        }
+        if (document.getElementById('exclude-own-repos').checked) {
+            options.exclude_own_repos = true;
+        }
+        if (document.getElementById('substantive-reviews-only').checked) {
+            options.substantive_reviews_only = true;
+        }
+        const repos = document.getElementById('scoped-repos').value
+            .split(',')
+            .map(repo => repo.trim())
+            .filter(repo => repo);
+        if (repos.length > 0) {
+            options.repos = repos;
+        }
+        if (document.getElementById('exclude-archived').checked) {
+            options.exclude_archived = true;
+        }
+        if (document.getElementById('exclude-templates').checked) {
+            options.exclude_templates = true;
+        }
+        if (document.getElementById('exclude-repos-without-commits').checked) {
+            options.exclude_repos_without_commits = true;
+        }
+        const histogram = document.getElementById('histogram').value;
+        if (histogram && ['yearly_commits', 'consecutive_days'].includes(formData.verification_type)) {
+            options.histogram = histogram;
+        }
+        if (formData.verification_type === 'consecutive_days') {
+            // ブラウザのタイムゾーンで日付を区切る
+            const timeZone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+            if (timeZone && timeZone !== 'UTC') {
+                options.time_zone = timeZone;
+            }
+            const streak = document.getElementById('streak-mode').value;
+            if (streak) {
+                options.streak = streak;
+            }
+            if (document.getElementById('contribution-events-only').checked) {
+                options.contribution_events_only = true;
+            }
+        }
+        const sampleSize = document.getElementById('sample-size').value;
+        if (sampleSize) {
+            options.sample_size = parseInt(sampleSize);
+        }
+        const minRepoAgeDays = document.getElementById('min-repo-age-days').value;
+        if (minRepoAgeDays) {
+            options.min_repo_age_days = parseInt(minRepoAgeDays);
+        }
+        const forkHandling = document.getElementById('fork-handling').value;
+        if (forkHandling) {
+            options.exclude_forks = forkHandling === 'exclude';
+        }
+        if (formData.verification_type === 'language_repos' || formData.verification_type === 'language_bytes') {
+            options.language = document.getElementById('language').value.trim();
+        }
+        if (formData.verification_type === 'org_membership') {
+            options.org = document.getElementById('org-name').value.trim();
+        }
+        if (formData.verification_type === 'repo_permission') {
+            options.repo = document.getElementById('repo-name').value.trim();
+            options.permission = document.getElementById('repo-permission').value;
+        }
+        if (Object.keys(options).length > 0) {
+            formData.options = options;
+        }
+        
         return formData;
     }
     
@@ -179,8 +353,75 @@ class GitHubVerifier {
         }
         
         // Threshold validation
-        if (formData.threshold && (formData.threshold < 1 || formData.threshold > 10000)) {
-            this.showError('Threshold must be between 1 and 10000');
+        const typeInfo = VERIFICATION_TYPES[formData.verification_type];
+        const maxThreshold = typeInfo.maxThreshold || 10000;
+        if (formData.threshold && (formData.threshold < 1 || formData.threshold > maxThreshold)) {
+            this.showError(`Threshold must be between 1 and ${maxThreshold}`);
+            return false;
+        }
+        
+        if (typeInfo.boolean && formData.disclosure === 'bucketed') {
+            this.showError('This verification type does not support range disclosure');
+            return false;
+        }
+        
+        if (typeInfo.boolean && formData.threshold && formData.threshold !== 1) {
+            this.showError('This verification type only accepts a threshold of 1');
+            return false;
+        }
+        
+        const options = formData.options || {};
+        if (formData.verification_type === 'org_membership' && !usernameRegex.test(options.org || '')) {
+            this.showError('Please enter a valid organization name');
+            return false;
+        }
+        
+        const repoRegex = /^[a-zA-Z0-9-]+\/[A-Za-z0-9._-]{1,100}$/;
+        if (formData.verification_type === 'repo_permission' && !repoRegex.test(options.repo || '')) {
+            this.showError('Please enter a repository as owner/name');
+            return false;
+        }
+        
+        const isLanguageType = formData.verification_type === 'language_repos' || formData.verification_type === 'language_bytes';
+        if (isLanguageType && !/^[A-Za-z0-9+#.' -]{1,50}$/.test(options.language || '')) {
+            this.showError('Please enter a language, e.g. Rust');
+            return false;
+        }
+        
+        const scopedRepos = options.repos || [];
+        if (formData.verification_type === 'repo_commits' && scopedRepos.length === 0) {
+            this.showError('Please enter at least one repository');
+            return false;
+        }
+        
+        if (scopedRepos.length > 10 || !scopedRepos.every(repo => repoRegex.test(repo))) {
+            this.showError('Please enter up to 10 repositories as owner/name, separated by commas');
+            return false;
+        }
+        
+        const timeWindow = options.window;
+        if (timeWindow && timeWindow.type === 'rolling' && (timeWindow.days < 1 || timeWindow.days > 3650)) {
+            this.showError('Time window must be between 1 and 3650 days');
+            return false;
+        }
+        
+        if (timeWindow && timeWindow.type === 'calendar_year' && !(timeWindow.year >= 2008 && timeWindow.year <= new Date().getUTCFullYear())) {
+            this.showError(`Year must be between 2008 and ${new Date().getUTCFullYear()}`);
+            return false;
+        }
+        
+        if (timeWindow && timeWindow.type === 'range' && (!timeWindow.from || !timeWindow.to || timeWindow.from > timeWindow.to)) {
+            this.showError('Please enter a valid date range');
+            return false;
+        }
+        
+        if (options.sample_size !== undefined && (options.sample_size < 1 || options.sample_size > 500)) {
+            this.showError('Sample size must be between 1 and 500 commits');
+            return false;
+        }
+        
+        if (options.min_repo_age_days !== undefined && (options.min_repo_age_days < 1 || options.min_repo_age_days > 3650)) {
+            this.showError('Minimum repository age must be between 1 and 3650 days');
             return false;
         }
         
@@ -236,12 +477,41 @@ class GitHubVerifier {
             }
         }
         
+        // Evidence表示（基準ごとの詳細）
+        const evidenceElement = document.getElementById('proof-evidence');
+        if (evidenceElement) {
+            if (proof.evidence) {
+                evidenceElement.textContent = JSON.stringify(proof.evidence, null, 2);
+                evidenceElement.parentElement.style.display = 'flex';
+            } else {
+                evidenceElement.parentElement.style.display = 'none';
+            }
+        }
+        
+        // 証明済みヒストグラム（SVG）表示
+        const histogramElement = document.getElementById('proof-histogram');
+        if (histogramElement) {
+            if (proof.histogram && proof.proof_hash) {
+                histogramElement.src = `/proof/${proof.proof_hash}/histogram.svg`;
+                histogramElement.parentElement.style.display = 'flex';
+            } else {
+                histogramElement.removeAttribute('src');
+                histogramElement.parentElement.style.display = 'none';
+            }
+        }
+        
         // 結果表示
         const resultElement = document.getElementById('proof-result');
         const statusBadge = document.getElementById('verification-status');
         
         if (resultElement && statusBadge) {
-            if (proof.meets_criteria) {
+            if (proof.status && proof.status.state === 'revoked') {
+                // 取り消し済みの証明書
+                resultElement.textContent = '⛔ REVOKED';
+                resultElement.style.color = '#721c24';
+                statusBadge.textContent = '⛔ REVOKED';
+                statusBadge.className = 'status-badge failed';
+            } else if (proof.meets_criteria) {
                 resultElement.textContent = '✅ VERIFIED';
                 resultElement.style.color = '#155724';
                 statusBadge.textContent = '✅ VERIFIED';
@@ -277,22 +547,12 @@ class GitHubVerifier {
     }
     
     formatVerificationType(type, threshold = null) {
-        const typeLabels = {
-            'yearly_commits': 'Commits/Year',
-            'consecutive_days': 'Days Streak',
-            'total_stars': 'Total Stars',
-            'public_repos': 'Public Repos'
-        };
-        
-        const defaults = {
-            'yearly_commits': 365,
-            'consecutive_days': 100,
-            'total_stars': 1000,
-            'public_repos': 10
-        };
-        
-        const actualThreshold = threshold || defaults[type];
-        return `${actualThreshold}+ ${typeLabels[type]}`;
+        const typeInfo = VERIFICATION_TYPES[type] || { label: type, defaultThreshold: '' };
+        if (typeInfo.boolean) {
+            return typeInfo.label;
+        }
+        const actualThreshold = threshold || typeInfo.defaultThreshold;
+        return `${actualThreshold}+ ${typeInfo.label}`;
     }
     
     formatDateTime(dateString) {
@@ -349,6 +609,30 @@ class GitHubVerifier {
         // フォームリセット
         document.getElementById('github-username').value = '';
         document.getElementById('threshold').value = '';
+        document.getElementById('threshold').disabled = false;
+        document.getElementById('disclosure').value = 'threshold';
+        document.getElementById('window-type').value = 'rolling';
+        document.getElementById('window-days').value = '';
+        document.getElementById('window-year').value = '';
+        document.getElementById('window-from').value = '';
+        document.getElementById('window-to').value = '';
+        this.updateWindowInputs();
+        document.getElementById('exclude-own-repos').checked = false;
+        document.getElementById('substantive-reviews-only').checked = false;
+        document.getElementById('scoped-repos').value = '';
+        document.getElementById('language').value = '';
+        document.getElementById('fork-handling').value = '';
+        document.getElementById('exclude-archived').checked = false;
+        document.getElementById('exclude-templates').checked = false;
+        document.getElementById('exclude-repos-without-commits').checked = false;
+        document.getElementById('min-repo-age-days').value = '';
+        document.getElementById('sample-size').value = '';
+        document.getElementById('streak-mode').value = '';
+        document.getElementById('histogram').value = '';
+        document.getElementById('contribution-events-only').checked = false;
+        document.getElementById('org-name').value = '';
+        document.getElementById('repo-name').value = '';
+        document.getElementById('repo-permission').value = 'push';
         document.querySelector('input[name="verification-type"][value="yearly_commits"]').checked = true;
         this.updateThresholdPlaceholder();
         this.updateVerificationLabels();